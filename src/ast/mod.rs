@@ -3,15 +3,23 @@ pub use node::*;
 
 use crate::{
     lexer::{Keyword, Token, TokenType},
-    position::Position,
     r#type::Type,
+    span::Span,
+    stack,
     stream::Stream,
     Error, ErrorType,
 };
-use std::convert::Into;
 
 pub struct AST {
     tokens: Stream<Token>,
+
+    /// Whether an identifier followed by an opening brace is parsed as a struct literal.
+    /// This is not the case in the condition of an if statement, where the brace opens its block instead.
+    allow_struct_literals: bool,
+
+    /// The errors found so far.
+    /// After an error, tokens are skipped until the end of the statement, and parsing continues from the next one.
+    errors: Vec<Error>,
 }
 
 impl AST {
@@ -19,206 +27,1226 @@ impl AST {
     pub fn new(tokens: Vec<Token>) -> Self {
         Self {
             tokens: Stream::new(tokens),
+            allow_struct_literals: true,
+            errors: vec![],
+        }
+    }
+
+    /// Parses the [tokens] into a list of statements, returning every error that was found if they could not be.
+    /// Statements are terminated by a semicolon, a significant newline, or the end of the input.
+    pub fn parse(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let mut statements = vec![];
+
+        while let Some(token) = self.tokens.peek() {
+            // Empty statements (e.g. blank lines, or `;;`) are skipped.
+            if token.token_type.is_statement_terminator() {
+                self.tokens.consume();
+                continue;
+            }
+
+            let start = self.tokens.index();
+            match self.parse_statement(token) {
+                Ok(statement) => statements.push(statement),
+                Err(error) => {
+                    self.recover(error, start);
+
+                    // A closing brace without an opening one cannot start a statement, so it is skipped.
+                    if self.tokens.index() == start {
+                        self.tokens.consume();
+                    }
+                },
+            }
+        }
+
+        if self.errors.is_empty() {
+            Ok(statements)
+        } else {
+            Err(std::mem::take(&mut self.errors))
+        }
+    }
+
+    /// Records an [error], and skips the rest of the statement that started at the token index [start].
+    /// The statement ends at the next terminator outside of any delimiters, or before a closing brace that ends the block
+    /// the statement is in.
+    fn recover(&mut self, error: Error, start: usize) {
+        self.errors.push(error);
+
+        // The delimiters that the statement has opened so far, which must be closed before the statement can end.
+        let mut delimiters = vec![];
+        for token in self.tokens.consumed_since(start) {
+            Self::track_delimiter(&mut delimiters, &token.token_type);
+        }
+
+        while let Some(token) = self.tokens.peek() {
+            match token.token_type {
+                TokenType::CloseBrace if delimiters.is_empty() => break,
+
+                _ if delimiters.is_empty() && token.token_type.is_statement_terminator() => {
+                    self.tokens.consume();
+                    break;
+                },
+
+                _ => Self::track_delimiter(&mut delimiters, &token.token_type),
+            }
+
+            self.tokens.consume();
+        }
+    }
+
+    /// Updates the open [delimiters], innermost last, with a token of the given type.
+    /// Each delimiter is stored as the type of the token that closes it, and a closing token which does not close the
+    /// innermost delimiter is ignored.
+    fn track_delimiter(delimiters: &mut Vec<TokenType>, token_type: &TokenType) {
+        match token_type {
+            TokenType::OpenParenthesis => delimiters.push(TokenType::CloseParenthesis),
+            TokenType::OpenBrace => delimiters.push(TokenType::CloseBrace),
+            TokenType::OpenBracket => delimiters.push(TokenType::CloseBracket),
+
+            TokenType::CloseParenthesis | TokenType::CloseBrace | TokenType::CloseBracket
+                if delimiters.last() == Some(token_type) =>
+            {
+                delimiters.pop();
+            },
+
+            _ => {},
+        }
+    }
+
+    /// Parses a single statement, starting at [token], including its terminator.
+    fn parse_statement(&mut self, token: Token) -> Result<Statement, Error> {
+        let statement = match token.token_type {
+            TokenType::Keyword(Keyword::Let) => {
+                self.tokens.consume();
+                self.parse_let_statement(token.span)?
+            },
+
+            TokenType::Keyword(Keyword::Return) => {
+                self.tokens.consume();
+                self.parse_return_statement(token.span)?
+            },
+
+            TokenType::Keyword(Keyword::If) => {
+                self.tokens.consume();
+                self.parse_if_statement(token.span)?
+            },
+
+            TokenType::Keyword(Keyword::Fn) => {
+                self.tokens.consume();
+                self.parse_function_statement(token.span)?
+            },
+
+            TokenType::Keyword(Keyword::Struct) => {
+                self.tokens.consume();
+                self.parse_struct_statement(token.span)?
+            },
+
+            TokenType::Keyword(Keyword::Type) => {
+                self.tokens.consume();
+                self.parse_type_alias_statement(token.span)?
+            },
+
+            TokenType::Keyword(Keyword::Newtype) => {
+                self.tokens.consume();
+                self.parse_newtype_statement(token.span)?
+            },
+
+            TokenType::Keyword(Keyword::Const) => {
+                self.tokens.consume();
+                self.parse_constant_statement(token.span)?
+            },
+
+            TokenType::Keyword(Keyword::Trait) => {
+                self.tokens.consume();
+                self.parse_trait_statement(token.span)?
+            },
+
+            TokenType::Keyword(Keyword::Impl) => {
+                self.tokens.consume();
+                self.parse_implementation_statement(token.span)?
+            },
+
+            _ => {
+                let expression = self.parse_expression()?;
+
+                // An expression followed by an equals sign is the place that an assignment assigns to.
+                match self.tokens.peek() {
+                    Some(Token {
+                        token_type: TokenType::Equals,
+                        ..
+                    }) => {
+                        self.tokens.consume();
+
+                        let value = self.parse_expression()?;
+                        Statement::Assignment {
+                            span: expression.span().to(value.span()),
+                            place: expression,
+                            value,
+                        }
+                    },
+
+                    _ => Statement::Expression(expression),
+                }
+            },
+        };
+
+        self.parse_end_of_statement()?;
+        Ok(statement)
+    }
+
+    /// Ensures that the statement that was just parsed is terminated.
+    /// <end of statement> ::= ";" | <newline> | "}" | <EOF>
+    fn parse_end_of_statement(&mut self) -> Result<(), Error> {
+        let Some(token) = self.tokens.peek() else {
+            return Ok(());
+        };
+
+        match token.token_type {
+            _ if token.token_type.is_statement_terminator() => {
+                self.tokens.consume();
+                Ok(())
+            },
+
+            // The closing brace of a block also ends the last statement in it, but is left for the block to consume.
+            TokenType::CloseBrace => Ok(()),
+
+            _ => Err(Error::new(
+                ErrorType::ExpectedEndOfStatement(token.token_type),
+                token.span,
+            )),
         }
     }
 
-    /// Parses the [tokens] into a list of statements.
-    pub fn parse(&mut self) -> Result<Vec<Statement>, Error> {
+    /// Returns the span from [start] to the end of the last token that was consumed, which covers everything parsed since.
+    fn span_from(&self, start: Span) -> Span {
+        self.tokens.previous().map_or(start, |token| start.to(token.span))
+    }
+
+    /// Returns an empty span just after the last token that was consumed, which is where the input ended unexpectedly.
+    /// A trailing newline is not counted, so that the span is at the end of the last line rather than after it.
+    fn end_of_input(&self) -> Span {
+        let end = match self.tokens.previous() {
+            Some(Token {
+                token_type: TokenType::Newline,
+                span,
+            }) => span.start,
+            Some(token) => token.span.end,
+            None => 0,
+        };
+
+        Span::new(end, end)
+    }
+
+    /// Whether the next token ends the current statement.
+    fn is_at_end_of_statement(&self) -> bool {
+        self.tokens
+            .peek()
+            .is_none_or(|token| token.token_type.is_statement_terminator() || token.token_type == TokenType::CloseBrace)
+    }
+
+    /// Parses a block of statements.
+    /// <block> ::= "{" <statement>* "}"
+    fn parse_block(&mut self) -> Result<Vec<Statement>, Error> {
+        self.check_stack()?;
+        self.expect_token(TokenType::OpenBrace)?;
+
         let mut statements = vec![];
 
         loop {
             let Some(token) = self.tokens.peek() else {
-                break;
+                return Err(Error::new(
+                    ErrorType::ExpectedToken(TokenType::CloseBrace),
+                    self.end_of_input(),
+                ));
             };
 
-            let statement = match token.token_type {
-                TokenType::Keyword(Keyword::Let) => {
+            match token.token_type {
+                TokenType::CloseBrace => {
                     self.tokens.consume();
-                    self.parse_let_statement(token.position)?
+                    break;
                 },
 
-                TokenType::Keyword(Keyword::Return) => {
+                _ if token.token_type.is_statement_terminator() => {
                     self.tokens.consume();
-                    self.parse_return_statement(token.position)?
                 },
 
                 _ => {
-                    let expression = self.parse_expression(token.position)?;
-                    Statement::Expression(expression)
+                    let start = self.tokens.index();
+                    match self.parse_statement(token) {
+                        Ok(statement) => statements.push(statement),
+
+                        // Nothing is left to recover from at the end of the input, where the block itself is unterminated.
+                        Err(error) if self.tokens.peek().is_none() => return Err(error),
+                        Err(error) => self.recover(error, start),
+                    }
+                },
+            }
+        }
+
+        Ok(statements)
+    }
+
+    /// Parses an if statement, after the `if` keyword.
+    /// if <expression> <block> (else (<if statement> | <block>))?
+    fn parse_if_statement(&mut self, start: Span) -> Result<Statement, Error> {
+        let outer_allow_struct_literals = std::mem::replace(&mut self.allow_struct_literals, false);
+        let condition = self.parse_expression();
+        self.allow_struct_literals = outer_allow_struct_literals;
+        let condition = condition?;
+
+        let then_branch = self.parse_block()?;
+
+        // The else keyword can be on the line after the closing brace.
+        if let Some(Token {
+            token_type: TokenType::Newline,
+            ..
+        }) = self.tokens.peek()
+        {
+            self.tokens.consume();
+
+            if !matches!(self.tokens.peek(), Some(token) if token.token_type == TokenType::Keyword(Keyword::Else)) {
+                self.tokens.unconsume();
+            }
+        }
+
+        let else_branch = match self.tokens.peek() {
+            Some(Token {
+                token_type: TokenType::Keyword(Keyword::Else),
+                ..
+            }) => {
+                self.tokens.consume();
+
+                match self.tokens.peek() {
+                    Some(Token {
+                        token_type: TokenType::Keyword(Keyword::If),
+                        span,
+                    }) => {
+                        self.tokens.consume();
+                        Some(vec![self.parse_if_statement(span)?])
+                    },
+
+                    _ => Some(self.parse_block()?),
+                }
+            },
+
+            _ => None,
+        };
+
+        Ok(Statement::If {
+            condition,
+            then_branch,
+            else_branch,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parses an expression, which is any number of operands separated by binary operators.
+    /// Operators with a higher precedence are applied first, and operators with the same precedence are applied from left
+    /// to right, e.g. `10 - 3 - 2` is `(10 - 3) - 2`.
+    /// <expression> ::= <operand> (<operator> <operand>)*
+    fn parse_expression(&mut self) -> Result<Expression, Error> {
+        self.check_stack()?;
+
+        // The operands and operators which are not part of an operation yet, as an operator with a higher precedence
+        // may follow them.
+        let mut operands = vec![self.parse_operand()?];
+        let mut operators: Vec<Operator> = Vec::new();
+
+        while let Some(operator) = self.tokens.peek().and_then(|token| Option::<Operator>::from(token.token_type)) {
+            self.tokens.consume();
+
+            while operators.last().is_some_and(|it| it.precedence() >= operator.precedence()) {
+                Self::apply_operator(&mut operands, &mut operators);
+            }
+
+            operators.push(operator);
+            operands.push(self.parse_operand()?);
+        }
+
+        while !operators.is_empty() {
+            Self::apply_operator(&mut operands, &mut operators);
+        }
+
+        Ok(operands.pop().expect("an expression should always have an operand"))
+    }
+
+    /// Replaces the last of the [operators] and the two [operands] on either side of it with a binary operation.
+    fn apply_operator(operands: &mut Vec<Expression>, operators: &mut Vec<Operator>) {
+        let (Some(operator), Some(right), Some(left)) = (operators.pop(), operands.pop(), operands.pop()) else {
+            unreachable!("each operator should have an operand on either side of it");
+        };
+
+        operands.push(Expression::BinaryOperation {
+            span: left.span().to(right.span()),
+            left: Box::new(left),
+            right: Box::new(right),
+            r#type: Type::default(),
+            operator,
+        });
+    }
+
+    /// Parses an operand of a binary operator, which may be checked against or cast to a type.
+    /// <operand> ::= <postfix expression> (("is" | "as") <type>)?
+    fn parse_operand(&mut self) -> Result<Expression, Error> {
+        let mut expression = self.parse_postfix_expression()?;
+
+        // A type check or a cast applies to the expression directly before it.
+        if let Some(Token {
+            token_type: TokenType::Keyword(keyword @ (Keyword::Is | Keyword::As)),
+            ..
+        }) = self.tokens.peek()
+        {
+            self.tokens.consume();
+
+            let target = self.parse_single_type()?;
+            let span = self.span_from(expression.span());
+            let value = Box::new(expression);
+
+            expression = match keyword {
+                Keyword::Is => Expression::Is { value, target, span },
+                _ => Expression::Cast { value, target, span },
+            };
+        }
+
+        Ok(expression)
+    }
+
+    /// Parses an expression that may be followed by any number of calls, indexes, field accesses, tuple accesses or method calls.
+    /// <postfix expression> ::= <primary expression> ("(" <arguments> ")" | "[" <expression> "]" | "." <integer> | "." <identifier> ("(" <arguments> ")")?)*
+    fn parse_postfix_expression(&mut self) -> Result<Expression, Error> {
+        let mut expression = self.parse_primary_expression()?;
+
+        while let Some(token) = self.tokens.peek() {
+            expression = match token.token_type {
+                TokenType::OpenParenthesis => {
+                    self.tokens.consume();
+
+                    let arguments = self.parse_separated(TokenType::CloseParenthesis, |ast| ast.parse_argument())?;
+
+                    Expression::FunctionCall {
+                        span: self.span_from(expression.span()),
+                        callee: Box::new(expression),
+                        arguments,
+                        r#type: Type::default(),
+                        tail_call: false,
+                    }
                 },
+
+                TokenType::OpenBracket => {
+                    self.tokens.consume();
+
+                    let index = self.parse_expression()?;
+                    self.expect_token(TokenType::CloseBracket)?;
+
+                    Expression::Index {
+                        span: self.span_from(expression.span()),
+                        value: Box::new(expression),
+                        index: Box::new(index),
+                        r#type: Type::default(),
+                    }
+                },
+
+                TokenType::Dot => {
+                    self.tokens.consume();
+
+                    // A dot followed by an integer accesses an element of a tuple.
+                    if let Some(Token {
+                        token_type: TokenType::IntegerLiteral(index),
+                        ..
+                    }) = self.tokens.peek()
+                    {
+                        self.tokens.consume();
+
+                        expression = Expression::TupleAccess {
+                            span: self.span_from(expression.span()),
+                            value: Box::new(expression),
+                            index: index as usize,
+                            r#type: Type::default(),
+                        };
+
+                        continue;
+                    }
+
+                    let identifier = self.parse_identifier()?;
+
+                    // A field followed by arguments is a method call.
+                    if let Some(Token {
+                        token_type: TokenType::OpenParenthesis,
+                        ..
+                    }) = self.tokens.peek()
+                    {
+                        self.tokens.consume();
+
+                        let arguments =
+                            self.parse_separated(TokenType::CloseParenthesis, |ast| ast.parse_expression())?;
+
+                        Expression::MethodCall {
+                            span: self.span_from(expression.span()),
+                            value: Box::new(expression),
+                            arguments,
+                            method: identifier,
+                            r#trait: None,
+                            r#type: Type::default(),
+                        }
+                    } else {
+                        Expression::FieldAccess {
+                            span: self.span_from(expression.span()),
+                            value: Box::new(expression),
+                            field: identifier,
+                            r#type: Type::default(),
+                        }
+                    }
+                },
+
+                _ => break,
             };
+        }
 
-            statements.push(statement);
+        Ok(expression)
+    }
+
+    /// Parses a list of elements separated by commas, up to and including the [closing] token.
+    /// A trailing comma is allowed, and newlines between elements are ignored.
+    /// <separated> ::= (<element> ("," <element>)* ","?)? <closing>
+    fn parse_separated<T>(
+        &mut self,
+        closing: TokenType,
+        mut parse_element: impl FnMut(&mut Self) -> Result<T, Error>,
+    ) -> Result<Vec<T>, Error> {
+        let mut elements = vec![];
+
+        loop {
+            self.skip_newlines();
+
+            let token = self.tokens.peek().ok_or(Error::new(ErrorType::UnexpectedEOF, self.end_of_input()))?;
+            if token.token_type == closing {
+                self.tokens.consume();
+                break;
+            }
+
+            elements.push(parse_element(self)?);
+            self.skip_newlines();
+
+            // Elements must either be separated by a comma, or followed by the closing token.
+            let token = self.tokens.peek().ok_or(Error::new(ErrorType::UnexpectedEOF, self.end_of_input()))?;
+            match token.token_type {
+                TokenType::Comma => {
+                    self.tokens.consume();
+                },
+
+                _ if token.token_type == closing => continue,
+
+                _ => return Err(Error::new(ErrorType::ExpectedToken(closing), token.span)),
+            }
         }
 
-        Ok(statements)
+        Ok(elements)
+    }
+
+    /// Parses a parenthesised element, after the opening parenthesis at [start].
+    /// If the element is followed by a comma, this is instead a tuple of the elements, which is created by [tuple]
+    /// along with its span, including the parentheses.
+    /// <parenthesised> ::= <element> ")" | <element> ("," <element>)+ ","? ")"
+    fn parse_parenthesised<T>(
+        &mut self,
+        start: Span,
+        mut parse_element: impl FnMut(&mut Self) -> Result<T, Error>,
+        tuple: impl FnOnce(Vec<T>, Span) -> T,
+    ) -> Result<T, Error> {
+        let first = parse_element(self)?;
+
+        let Some(Token {
+            token_type: TokenType::Comma,
+            ..
+        }) = self.tokens.peek()
+        else {
+            self.expect_token(TokenType::CloseParenthesis)?;
+            return Ok(first);
+        };
+
+        self.tokens.consume();
+
+        let mut elements = vec![first];
+        elements.extend(self.parse_separated(TokenType::CloseParenthesis, parse_element)?);
+
+        // A single element followed by a trailing comma is still just that element, as tuples have at least two elements.
+        if elements.len() == 1 {
+            return Ok(elements.remove(0));
+        }
+
+        Ok(tuple(elements, self.span_from(start)))
     }
 
-    /// Parses an expression.
-    /// <expression> ::= <identifier> | <literal>
-    fn parse_expression(&mut self, last_position: Position) -> Result<Expression, Error> {
+    /// Skips any newlines, for places where they are not significant, such as between the fields of a struct.
+    fn skip_newlines(&mut self) {
+        while let Some(Token {
+            token_type: TokenType::Newline,
+            ..
+        }) = self.tokens.peek()
+        {
+            self.tokens.consume();
+        }
+    }
+
+    /// Parses a primary expression.
+    /// <primary expression> ::= <identifier> | <literal> | "(" <expression> ")" | <tuple> | <lambda> | <list> | <struct literal>
+    fn parse_primary_expression(&mut self) -> Result<Expression, Error> {
         // We don't consume this as the caller may be able to parse it as a statement in the case that it is not an expression.
         let Some(token) = self.tokens.consume() else {
-            return Err(Error::new(ErrorType::UnexpectedEOF, last_position));
+            return Err(Error::new(ErrorType::UnexpectedEOF, self.end_of_input()));
         };
 
         let expression = match token.token_type {
             TokenType::Identifier(value) => {
-                let identifier = Identifier::new(value, token.position);
-
-                // We need to check if the next token is an open parenthesis.
-                let next_token = self.tokens.peek();
+                let identifier = Identifier::new(value, token.span);
 
-                if let Some(Token {
-                    token_type: TokenType::OpenParenthesis,
-                    ..
-                }) = next_token
+                // An identifier followed by an opening brace is a struct literal.
+                if let (
+                    Some(Token {
+                        token_type: TokenType::OpenBrace,
+                        ..
+                    }),
+                    true,
+                ) = (self.tokens.peek(), self.allow_struct_literals)
                 {
-                    // This is a function call.
-                    self.tokens.consume();
+                    return self.parse_struct_literal(identifier);
+                }
 
-                    let mut arguments = vec![];
-                    loop {
-                        let next_token = self.tokens.peek();
-
-                        if let Some(Token {
-                            token_type: TokenType::CloseParenthesis,
-                            ..
-                        }) = next_token
-                        {
-                            self.tokens.consume();
-                            break;
-                        }
+                Expression::Identifier(Type::Unresolved, identifier)
+            },
 
-                        let argument = self.parse_expression(token.position)?;
-                        arguments.push(argument);
+            TokenType::IntegerLiteral(value) => Expression::IntegerLiteral(value, token.span),
 
-                        let next_token = self.tokens.peek();
+            TokenType::StringLiteral(value) => Expression::StringLiteral(value, token.span),
 
-                        if let Some(Token {
-                            token_type: TokenType::Comma,
-                            ..
-                        }) = next_token
-                        {
-                            self.tokens.consume();
-                        } else {
-                            continue;
-                        }
-                    }
+            TokenType::Keyword(Keyword::True) => Expression::BooleanLiteral(true, token.span),
+            TokenType::Keyword(Keyword::False) => Expression::BooleanLiteral(false, token.span),
 
-                    return Ok(Expression::FunctionCall {
-                        identifier,
-                        arguments,
+            TokenType::OpenParenthesis => {
+                // Struct literals can always be used within parentheses.
+                let outer_allow_struct_literals = std::mem::replace(&mut self.allow_struct_literals, true);
+
+                let expression = self.parse_parenthesised(
+                    token.span,
+                    |ast| ast.parse_expression(),
+                    |elements, span| Expression::Tuple {
+                        elements,
+                        span,
                         r#type: Type::default(),
-                    });
-                }
+                    },
+                );
 
-                Expression::Identifier(Type::Unresolved(None), identifier)
+                self.allow_struct_literals = outer_allow_struct_literals;
+                expression?
             },
 
-            TokenType::IntegerLiteral(value) => Expression::IntegerLiteral(value),
+            TokenType::OpenBracket => Expression::List {
+                elements: self.parse_separated(TokenType::CloseBracket, |ast| ast.parse_expression())?,
+                span: self.span_from(token.span),
+                r#type: Type::default(),
+            },
 
-            TokenType::StringLiteral(value) => Expression::StringLiteral(value),
+            TokenType::Pipe => self.parse_lambda(token.span)?,
 
             // Unable to parse the token as an expression.
             _ => {
                 self.tokens.unconsume();
-                return Err(Error::new(ErrorType::UnexpectedToken(token.token_type), token.position));
+                return Err(Error::new(ErrorType::UnexpectedToken(token.token_type), token.span));
+            },
+        };
+
+        Ok(expression)
+    }
+
+    /// Parses a struct literal, after the name of the struct.
+    /// <struct literal> ::= <identifier> "{" (<identifier> ":" <expression> ("," <identifier> ":" <expression>)*)? "}"
+    fn parse_struct_literal(&mut self, identifier: Identifier) -> Result<Expression, Error> {
+        self.expect_token(TokenType::OpenBrace)?;
+
+        let fields = self.parse_separated(TokenType::CloseBrace, |ast| {
+            let field = ast.parse_identifier()?;
+            ast.expect_token(TokenType::Colon)?;
+
+            let value = ast.parse_expression()?;
+            Ok((field, value))
+        })?;
+
+        Ok(Expression::StructLiteral {
+            span: self.span_from(identifier.span),
+            identifier,
+            fields,
+            r#type: Type::default(),
+        })
+    }
+
+    /// Parses a lambda, after the opening pipe.
+    /// <lambda> ::= "|" <parameters> "|" (<block> | <expression>)
+    fn parse_lambda(&mut self, start: Span) -> Result<Expression, Error> {
+        let parameters = self.parse_parameters(TokenType::Pipe)?;
+
+        // The body of a lambda can either be a block, or a single expression which is returned.
+        let body = if let Some(Token {
+            token_type: TokenType::OpenBrace,
+            ..
+        }) = self.tokens.peek()
+        {
+            self.parse_block()?
+        } else {
+            let value = self.parse_expression()?;
+            vec![Statement::Return {
+                span: value.span(),
+                value: Some(value),
+            }]
+        };
+
+        Ok(Expression::Function(Function {
+            identifier: None,
+            type_parameters: vec![],
+            parameters,
+            return_type: Type::Unresolved,
+            body,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Parses a function declaration.
+    /// fn <identifier><type parameters?>(<parameters>)<: Type?> <block>
+    fn parse_function_statement(&mut self, start: Span) -> Result<Statement, Error> {
+        Ok(Statement::Function(self.parse_function(start)?))
+    }
+
+    /// Parses a named function, after the `fn` keyword.
+    fn parse_function(&mut self, start: Span) -> Result<Function, Error> {
+        let identifier = self.parse_identifier()?;
+        let type_parameters = self.parse_type_parameters()?;
+
+        self.expect_token(TokenType::OpenParenthesis)?;
+        let parameters = self.parse_parameters(TokenType::CloseParenthesis)?;
+
+        // If no return type is declared, it is inferred from the function's return statements.
+        let return_type = self.parse_return_type()?.unwrap_or_default();
+        let body = self.parse_block()?;
+
+        Ok(Function {
+            span: self.span_from(start),
+            identifier: Some(identifier),
+            type_parameters,
+            parameters,
+            return_type,
+            body,
+        })
+    }
+
+    /// Parses a return type, if there is one.
+    /// <return type> ::= ":" <type>
+    fn parse_return_type(&mut self) -> Result<Option<Type>, Error> {
+        let Some(Token {
+            token_type: TokenType::Colon,
+            ..
+        }) = self.tokens.peek()
+        else {
+            return Ok(None);
+        };
+
+        self.tokens.consume();
+        Ok(Some(self.parse_type_identifier()?))
+    }
+
+    /// Parses a trait declaration.
+    /// Methods can be separated by semicolons or newlines.
+    /// trait <identifier> { (fn <identifier>(<parameters>)<: Type?>)* }
+    fn parse_trait_statement(&mut self, start: Span) -> Result<Statement, Error> {
+        let identifier = self.parse_identifier()?;
+
+        let methods = self.parse_members(|ast, _| {
+            let identifier = ast.parse_identifier()?;
+
+            ast.expect_token(TokenType::OpenParenthesis)?;
+            let parameters = ast.parse_parameters(TokenType::CloseParenthesis)?;
+
+            // A method signature has no body to infer its return type from, so it returns nothing unless declared.
+            let return_type = ast.parse_return_type()?.unwrap_or(Type::Void);
+
+            Ok(Signature {
+                identifier,
+                parameters,
+                return_type,
+            })
+        })?;
+
+        Ok(Statement::Trait(Trait {
+            identifier,
+            methods,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Parses an implementation of a trait, or of a type's own methods.
+    /// impl<type parameters?> (<identifier> for)? <type> { <function>* }
+    fn parse_implementation_statement(&mut self, start: Span) -> Result<Statement, Error> {
+        let type_parameters = self.parse_type_parameters()?;
+        let r#type = self.parse_type_identifier()?;
+
+        // If the type is followed by `for`, it was actually the name of the trait being implemented.
+        let (r#trait, r#type) = match (r#type, self.tokens.peek()) {
+            (
+                Type::Named(name, arguments, span),
+                Some(Token {
+                    token_type: TokenType::Keyword(Keyword::For),
+                    ..
+                }),
+            ) if arguments.is_empty() => {
+                self.tokens.consume();
+
+                let r#trait = Identifier::new(name, span);
+                (Some(r#trait), self.parse_type_identifier()?)
             },
+
+            (r#type, _) => (None, r#type),
+        };
+
+        let methods = self.parse_members(|ast, span| ast.parse_function(span))?;
+
+        Ok(Statement::Implementation(Implementation {
+            type_parameters,
+            r#trait,
+            r#type,
+            methods,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Parses the methods in the body of a trait or an implementation, each of which starts with `fn`.
+    /// <members> ::= "{" ("fn" <member>)* "}"
+    fn parse_members<T>(
+        &mut self,
+        mut parse_member: impl FnMut(&mut Self, Span) -> Result<T, Error>,
+    ) -> Result<Vec<T>, Error> {
+        self.expect_token(TokenType::OpenBrace)?;
+
+        let mut members = vec![];
+        loop {
+            let token = self.tokens.consume().ok_or(Error::new(
+                ErrorType::ExpectedToken(TokenType::CloseBrace),
+                self.end_of_input(),
+            ))?;
+
+            match token.token_type {
+                TokenType::CloseBrace => break,
+                _ if token.token_type.is_statement_terminator() => continue,
+
+                TokenType::Keyword(Keyword::Fn) => members.push(parse_member(self, token.span)?),
+
+                _ => {
+                    return Err(Error::new(
+                        ErrorType::ExpectedToken(TokenType::Keyword(Keyword::Fn)),
+                        token.span,
+                    ))
+                },
+            }
+        }
+
+        Ok(members)
+    }
+
+    /// Parses a type alias, after the `type` keyword.
+    /// type <identifier> = <type>
+    fn parse_type_alias_statement(&mut self, start: Span) -> Result<Statement, Error> {
+        let identifier = self.parse_identifier()?;
+        self.expect_token(TokenType::Equals)?;
+
+        Ok(Statement::TypeAlias {
+            r#type: self.parse_type_identifier()?,
+            identifier,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parses a newtype declaration, after the `newtype` keyword.
+    /// newtype <identifier> = <type>
+    fn parse_newtype_statement(&mut self, start: Span) -> Result<Statement, Error> {
+        let identifier = self.parse_identifier()?;
+        self.expect_token(TokenType::Equals)?;
+
+        Ok(Statement::Newtype(Newtype {
+            r#type: self.parse_type_identifier()?,
+            identifier,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Parses a struct declaration.
+    /// Fields can be separated by commas or newlines.
+    /// struct <identifier><type parameters?> { (<identifier>: <type>)* }
+    fn parse_struct_statement(&mut self, start: Span) -> Result<Statement, Error> {
+        let identifier = self.parse_identifier()?;
+        let type_parameters = self.parse_type_parameters()?;
+
+        self.expect_token(TokenType::OpenBrace)?;
+
+        let mut fields = vec![];
+        loop {
+            let token = self.tokens.consume().ok_or(Error::new(
+                ErrorType::ExpectedToken(TokenType::CloseBrace),
+                self.end_of_input(),
+            ))?;
+
+            match token.token_type {
+                TokenType::CloseBrace => break,
+                TokenType::Comma | TokenType::Newline => continue,
+
+                TokenType::Identifier(name) => {
+                    let field = Identifier::new(name, token.span);
+                    self.expect_token(TokenType::Colon)?;
+
+                    let r#type = self.parse_type_identifier()?;
+                    fields.push(Field {
+                        identifier: field,
+                        r#type,
+                    });
+                },
+
+                _ => return Err(Error::new(ErrorType::ExpectedAnyIdentifier, token.span)),
+            }
+        }
+
+        Ok(Statement::Struct(Struct {
+            identifier,
+            type_parameters,
+            fields,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Parses the type parameters of a declaration, if there are any.
+    /// <type parameters> ::= "<" <type parameter> ("," <type parameter>)* ">"
+    /// <type parameter> ::= <identifier> (":" <identifier> ("+" <identifier>)*)?
+    fn parse_type_parameters(&mut self) -> Result<Vec<TypeParameter>, Error> {
+        let Some(Token {
+            token_type: TokenType::LessThan,
+            ..
+        }) = self.tokens.peek()
+        else {
+            return Ok(vec![]);
         };
 
-        // If the next token is an operator, this is a binary operation expression.
-        let next_token = self.tokens.peek();
-        if let Some(operator) = next_token.and_then(|token| token.token_type.into()) {
+        self.tokens.consume();
+        self.parse_separated(TokenType::GreaterThan, |ast| {
+            let identifier = ast.parse_identifier()?;
+
+            let mut bounds = vec![];
+            if let Some(Token {
+                token_type: TokenType::Colon,
+                ..
+            }) = ast.tokens.peek()
+            {
+                ast.tokens.consume();
+                bounds.push(ast.parse_identifier()?);
+
+                while let Some(Token {
+                    token_type: TokenType::Plus,
+                    ..
+                }) = ast.tokens.peek()
+                {
+                    ast.tokens.consume();
+                    bounds.push(ast.parse_identifier()?);
+                }
+            }
+
+            Ok(TypeParameter { identifier, bounds })
+        })
+    }
+
+    /// Parses a list of parameters, up to and including the [closing] token.
+    /// <parameters> ::= (<parameter> ("," <parameter>)*)? <closing>
+    /// <parameter> ::= "..."? <pattern> (":" <type>)? ("=" <expression>)?
+    fn parse_parameters(&mut self, closing: TokenType) -> Result<Vec<Parameter>, Error> {
+        // The parameters of a lambda are closed by a pipe, so a union type must be parenthesised there.
+        let is_lambda = closing == TokenType::Pipe;
+
+        self.parse_separated(closing, |ast| {
+            // Like default values, variadic parameters are only allowed for functions which can be called by name.
+            let variadic = match ast.tokens.peek() {
+                Some(Token {
+                    token_type: TokenType::Ellipsis,
+                    ..
+                }) if !is_lambda => {
+                    ast.tokens.consume();
+                    true
+                },
+
+                _ => false,
+            };
+
+            let pattern = ast.parse_pattern()?;
+
+            let r#type = if let Some(Token {
+                token_type: TokenType::Colon,
+                ..
+            }) = ast.tokens.peek()
+            {
+                ast.tokens.consume();
+
+                if is_lambda {
+                    ast.parse_single_type()?
+                } else {
+                    ast.parse_type_identifier()?
+                }
+            } else {
+                Type::Unresolved
+            };
+
+            // Lambdas are called through variables, rather than by name, so their parameters cannot have default values.
+            // A variadic parameter is an empty list by default.
+            let default = match ast.tokens.peek() {
+                Some(Token {
+                    token_type: TokenType::Equals,
+                    ..
+                }) if !is_lambda && !variadic => {
+                    ast.tokens.consume();
+                    Some(ast.parse_expression()?)
+                },
+
+                _ => None,
+            };
+
+            Ok(Parameter {
+                pattern,
+                r#type,
+                default,
+                variadic,
+            })
+        })
+    }
+
+    /// Parses an argument of a function call.
+    /// <argument> ::= (<identifier> ":")? <expression>
+    fn parse_argument(&mut self) -> Result<Argument, Error> {
+        if let Some(Token {
+            token_type: TokenType::Identifier(name),
+            span,
+        }) = self.tokens.peek()
+        {
             self.tokens.consume();
 
-            let right_expression = self.parse_expression(token.position)?;
+            // An identifier followed by a colon is the name of the parameter that the argument is passed to.
+            if let Some(Token {
+                token_type: TokenType::Colon,
+                ..
+            }) = self.tokens.peek()
+            {
+                self.tokens.consume();
 
-            return Ok(Expression::BinaryOperation {
-                left: Box::new(expression),
-                right: Box::new(right_expression),
-                r#type: Type::default(),
-                position: token.position,
-                operator,
-            });
+                return Ok(Argument {
+                    name: Some(Identifier::new(name, span)),
+                    value: self.parse_expression()?,
+                });
+            }
+
+            self.tokens.unconsume();
         }
 
-        Ok(expression)
+        Ok(Argument::positional(self.parse_expression()?))
+    }
+
+    /// Parses a pattern.
+    /// <pattern> ::= <identifier> | "_" | "(" <pattern> ("," <pattern>)+ ")"
+    fn parse_pattern(&mut self) -> Result<Pattern, Error> {
+        if let Some(Token {
+            token_type: TokenType::OpenParenthesis,
+            span,
+        }) = self.tokens.peek()
+        {
+            self.tokens.consume();
+
+            return self.parse_parenthesised(span, |ast| ast.parse_pattern(), Pattern::Tuple);
+        }
+
+        let identifier = self.parse_identifier()?;
+        if identifier.name == "_" {
+            return Ok(Pattern::Wildcard(identifier.span));
+        }
+
+        Ok(Pattern::Identifier(identifier))
     }
 
     /// Parses a let statement.
-    /// let <identifier><: Type?> = <expression>
-    fn parse_let_statement(&mut self, last_position: Position) -> Result<Statement, Error> {
-        let identifier = self.parse_identifier(last_position)?;
+    /// let <pattern><: Type?> = <expression>
+    fn parse_let_statement(&mut self, start: Span) -> Result<Statement, Error> {
+        let mutable = if let Some(Token {
+            token_type: TokenType::Keyword(Keyword::Mut),
+            ..
+        }) = self.tokens.peek()
+        {
+            self.tokens.consume();
+            true
+        } else {
+            false
+        };
+
+        let pattern = self.parse_pattern()?;
 
         // The next token can either be a colon or an equals sign.
-        let token = self.tokens.consume().ok_or_else(|| Error::new(ErrorType::UnexpectedEOF, identifier.position))?;
-        let r#type = match token.token_type {
+        let token = self.tokens.consume().ok_or_else(|| Error::new(ErrorType::UnexpectedEOF, self.end_of_input()))?;
+        let (r#type, type_span) = match token.token_type {
             TokenType::Colon => {
-                let value = self.parse_type_identifier(token.position).unwrap_or(Type::Unresolved(None));
+                let type_start = self.tokens.peek().map_or(token.span, |it| it.span);
+                let value = self.parse_type_identifier()?;
+                let type_span = self.span_from(type_start);
 
                 // We must also ensure that the next token is an equals sign.
-                self.tokens
-                    .consume()
-                    .ok_or_else(|| Error::new(ErrorType::ExpectedToken(TokenType::Equals), token.position))?;
+                self.expect_token(TokenType::Equals)?;
 
-                value
+                (value, Some(type_span))
             },
 
             // Nothing else to parse.
-            TokenType::Equals => Type::Unresolved(None),
+            TokenType::Equals => (Type::Unresolved, None),
 
             // Invalid token.
-            _ => return Err(Error::new(ErrorType::UnexpectedToken(token.token_type), token.position)),
+            _ => return Err(Error::new(ErrorType::UnexpectedToken(token.token_type), token.span)),
         };
 
-        let value = self.parse_expression(token.position)?;
+        let value = self.parse_expression()?;
 
         Ok(Statement::Let {
-            identifier,
+            pattern,
             value,
             r#type,
-            position: token.position,
+            type_span,
+            mutable,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parses a constant declaration, after the `const` keyword.
+    /// const <identifier> = <expression>
+    fn parse_constant_statement(&mut self, start: Span) -> Result<Statement, Error> {
+        let identifier = self.parse_identifier()?;
+        self.expect_token(TokenType::Equals)?;
+
+        Ok(Statement::Constant {
+            identifier,
+            value: self.parse_expression()?,
+            span: self.span_from(start),
         })
     }
 
     /// Parses a return statement.
     /// return <expression?>
-    fn parse_return_statement(&mut self, last_position: Position) -> Result<Statement, Error> {
-        let value = self.parse_expression(last_position).ok();
+    fn parse_return_statement(&mut self, start: Span) -> Result<Statement, Error> {
+        // A return statement without a value is immediately terminated.
+        let value = if self.is_at_end_of_statement() {
+            None
+        } else {
+            Some(self.parse_expression()?)
+        };
 
         Ok(Statement::Return {
             value,
-            position: last_position,
+            span: self.span_from(start),
         })
     }
 
     /// Parses an identifier.
-    fn parse_identifier(&mut self, last_position: Position) -> Result<Identifier, Error> {
-        let token = self.tokens.consume().ok_or_else(|| Error::new(ErrorType::ExpectedAnyIdentifier, last_position))?;
+    fn parse_identifier(&mut self) -> Result<Identifier, Error> {
+        let token =
+            self.tokens.consume().ok_or_else(|| Error::new(ErrorType::ExpectedAnyIdentifier, self.end_of_input()))?;
 
         if let TokenType::Identifier(name) = token.token_type {
-            Ok(Identifier {
-                name,
-                position: token.position,
-            })
+            Ok(Identifier { name, span: token.span })
         } else {
-            Err(Error::new(ErrorType::ExpectedAnyIdentifier, token.position))
+            Err(Error::new(ErrorType::ExpectedAnyIdentifier, token.span))
         }
     }
 
-    /// Parses a type identifier.
-    fn parse_type_identifier(&mut self, last_position: Position) -> Result<Type, Error> {
-        let token = self.tokens.consume().ok_or_else(|| Error::new(ErrorType::UnexpectedEOF, last_position))?;
+    /// Parses a type identifier, which may be a union of several types.
+    /// <type> ::= <single type> ("|" <single type>)*
+    fn parse_type_identifier(&mut self) -> Result<Type, Error> {
+        let mut members = vec![self.parse_single_type()?];
 
-        if let TokenType::Identifier(name) = token.token_type {
-            Ok(match name.as_str() {
-                "Integer" => Type::Integer,
-                "String" => Type::String,
-                _ => Type::Unresolved(Some(name)),
-            })
+        while let Some(Token {
+            token_type: TokenType::Pipe,
+            ..
+        }) = self.tokens.peek()
+        {
+            self.tokens.consume();
+            members.push(self.parse_single_type()?);
+        }
+
+        Ok(Type::union(members))
+    }
+
+    /// Parses a type identifier which is not a union, unless it is parenthesised.
+    /// <single type> ::= <identifier> ("<" <type> ("," <type>)* ">")? | "fn" "(" (<type> ("," <type>)*)? ")" <: Type?> | "(" <type> ("," <type>)* ")"
+    fn parse_single_type(&mut self) -> Result<Type, Error> {
+        let token = self.tokens.consume().ok_or_else(|| Error::new(ErrorType::UnexpectedEOF, self.end_of_input()))?;
+
+        match token.token_type {
+            TokenType::Identifier(name) => {
+                let arguments = if let Some(Token {
+                    token_type: TokenType::LessThan,
+                    ..
+                }) = self.tokens.peek()
+                {
+                    self.tokens.consume();
+                    self.parse_separated(TokenType::GreaterThan, |ast| ast.parse_type_identifier())?
+                } else {
+                    vec![]
+                };
+
+                Ok(match (name.as_str(), arguments.as_slice()) {
+                    ("Any", []) => Type::Any,
+                    ("Integer", []) => Type::Integer,
+                    ("String", []) => Type::String,
+                    ("Boolean", []) => Type::Boolean,
+                    ("List", [element_type]) => Type::List(Box::new(element_type.clone())),
+
+                    // Whether this type exists, and takes this many type arguments, is checked by the resolver.
+                    _ => Type::Named(name, arguments, self.span_from(token.span)),
+                })
+            },
+
+            TokenType::Keyword(Keyword::Fn) => {
+                self.expect_token(TokenType::OpenParenthesis)?;
+
+                let parameters =
+                    self.parse_separated(TokenType::CloseParenthesis, |ast| ast.parse_type_identifier())?;
+
+                // Unlike a function declaration, a function type without a return type returns nothing.
+                let return_type = self.parse_return_type()?.unwrap_or(Type::Void);
+
+                Ok(Type::Function(parameters, Box::new(return_type)))
+            },
+
+            TokenType::OpenParenthesis => self.parse_parenthesised(
+                token.span,
+                |ast| ast.parse_type_identifier(),
+                |types, _| Type::Tuple(types),
+            ),
+
+            _ => Err(Error::new(ErrorType::ExpectedAnyIdentifier, token.span)),
+        }
+    }
+
+    /// Consumes the next token, ensuring that it is of the [expected] type.
+    /// Ensures that there is enough stack space left to parse an expression or block nested inside the current one.
+    fn check_stack(&self) -> Result<(), Error> {
+        if !stack::is_exhausted() {
+            return Ok(());
+        }
+
+        let span = self.tokens.peek().map_or_else(|| self.end_of_input(), |token| token.span);
+        Err(Error::new(ErrorType::NestedTooDeeply, span)
+            .with_help("split it up, e.g. by storing parts of it in variables"))
+    }
+
+    fn expect_token(&mut self, expected: TokenType) -> Result<Token, Error> {
+        let token = self.tokens.consume().ok_or(Error::new(ErrorType::UnexpectedEOF, self.end_of_input()))?;
+
+        if token.token_type == expected {
+            Ok(token)
         } else {
-            Err(Error::new(ErrorType::ExpectedAnyIdentifier, token.position))
+            Err(Error::new(ErrorType::ExpectedToken(expected), token.span))
         }
     }
 }
@@ -1,14 +1,18 @@
 use std::hash::Hash;
 
-use crate::{lexer::TokenType, position::Position, r#type::Type};
+use crate::{
+    lexer::TokenType,
+    r#type::{GenericParameter, Type},
+    span::Span,
+};
 
 /// The different types of statements that can be parsed.
 #[derive(Debug, Clone)]
 pub enum Statement {
-    /// A statement that assigns a value to a variable.
+    /// A statement that assigns a value to a variable, or to the variables of a pattern, e.g. `let (a, b) = pair`.
     Let {
-        /// The name of the variable.
-        identifier: Identifier,
+        /// The pattern that the value is matched against.
+        pattern: Pattern,
 
         /// The value to assign to the variable.
         value: Expression,
@@ -16,18 +20,93 @@ pub enum Statement {
         /// The declared type of the variable.
         r#type: Type,
 
-        /// The position of the let statement in the source code.
-        position: Position,
+        /// The span of the declared type in the source code, if it was declared.
+        type_span: Option<Span>,
+
+        /// Whether the variables were declared with `let mut`, allowing them to be modified in place.
+        mutable: bool,
+
+        /// The span of the let statement in the source code.
+        span: Span,
+    },
+
+    /// A statement that replaces the value of a variable, a field, a tuple element or a list element, e.g. `point.x = 1`.
+    Assignment {
+        /// The variable, field or element being assigned to.
+        place: Expression,
+
+        /// The new value.
+        value: Expression,
+
+        /// The span of the assignment in the source code.
+        span: Span,
+    },
+
+    /// A constant, whose value is evaluated before the program runs, e.g. `const MAX = 10 * 1024`.
+    Constant {
+        /// The name of the constant.
+        identifier: Identifier,
+
+        /// The value of the constant, which the resolver replaces with a literal.
+        value: Expression,
+
+        /// The span of the constant in the source code.
+        span: Span,
     },
 
     Return {
         /// The value to return.
         value: Option<Expression>,
 
-        /// The position of the return statement in the source code.
-        position: Position,
+        /// The span of the return statement in the source code.
+        span: Span,
     },
 
+    /// A statement that only runs its first branch if the condition is true, and otherwise runs its second branch if it has one,
+    /// e.g. `if x is Integer { ... } else { ... }`.
+    If {
+        /// The condition, which must be a boolean.
+        condition: Expression,
+
+        /// The statements that are run if the condition is true.
+        then_branch: Vec<Statement>,
+
+        /// The statements that are run if the condition is false.
+        /// An `else if` is represented as an else branch containing only another if statement.
+        else_branch: Option<Vec<Statement>>,
+
+        /// The span of the if statement in the source code.
+        span: Span,
+    },
+
+    /// A named function declaration, e.g. `fn double(x: Integer): Integer { return x * 2 }`.
+    Function(Function),
+
+    /// A struct declaration, e.g. `struct Pair<A, B> { first: A, second: B }`.
+    Struct(Struct),
+
+    /// A type alias, which is another name for an existing type, e.g. `type UserId = Integer`.
+    TypeAlias {
+        /// The name of the alias.
+        identifier: Identifier,
+
+        /// The type that the alias stands for.
+        r#type: Type,
+
+        /// The span of the type alias in the source code.
+        span: Span,
+    },
+
+    /// A newtype declaration, e.g. `newtype Meters = Integer`.
+    Newtype(Newtype),
+
+    /// A trait declaration, e.g. `trait Describe { fn describe(self): String }`.
+    Trait(Trait),
+
+    /// An implementation of a trait for a type, e.g. `impl Describe for Integer { ... }`,
+    /// or of a struct's own methods, e.g. `impl Point { ... }`.
+    Implementation(Implementation),
+
     /// A statement that wraps an expression.
     Expression(Expression),
 }
@@ -38,18 +117,18 @@ pub struct Identifier {
     /// The name of the identifier.
     pub name: String,
 
-    // The position of the identifier in the source code.
-    pub position: Position,
+    // The span of the identifier in the source code.
+    pub span: Span,
 }
 
-/// We can compare identifiers by their name, not their position.
+/// We can compare identifiers by their name, not their span.
 impl PartialEq for Identifier {
     fn eq(&self, other: &Self) -> bool {
         self.name == other.name
     }
 }
 
-/// We can hash identifiers by their name, not their position.
+/// We can hash identifiers by their name, not their span.
 impl Hash for Identifier {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.name.hash(state);
@@ -58,8 +137,54 @@ impl Hash for Identifier {
 
 impl Identifier {
     /// Creates a new identifier.
-    pub fn new(name: String, position: Position) -> Self {
-        Self { name, position }
+    pub fn new(name: String, span: Span) -> Self {
+        Self { name, span }
+    }
+}
+
+/// A pattern that a value is matched against, binding the variables in it.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// A variable, which is bound to the whole value.
+    Identifier(Identifier),
+
+    /// A pattern that destructures a tuple, matching each of its elements against a pattern, e.g. `(a, (b, c))`.
+    Tuple(Vec<Pattern>, Span),
+
+    /// An underscore, which matches any value without binding it to a variable.
+    Wildcard(Span),
+}
+
+/// Patterns are written as they appear in the source code, e.g. `(a, b)`.
+impl std::fmt::Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pattern::Identifier(identifier) => write!(f, "{}", identifier.name),
+            Pattern::Wildcard(_) => write!(f, "_"),
+            Pattern::Tuple(patterns, _) => {
+                let patterns = patterns.iter().map(Pattern::to_string).collect::<Vec<_>>();
+                write!(f, "({})", patterns.join(", "))
+            },
+        }
+    }
+}
+
+impl Pattern {
+    /// Returns the span of the pattern in the source code.
+    pub fn span(&self) -> Span {
+        match self {
+            Pattern::Identifier(identifier) => identifier.span,
+            Pattern::Tuple(_, span) | Pattern::Wildcard(span) => *span,
+        }
+    }
+
+    /// Returns the variables that the pattern binds, in the order that they appear.
+    pub fn identifiers(&self) -> Vec<&Identifier> {
+        match self {
+            Pattern::Identifier(identifier) => vec![identifier],
+            Pattern::Tuple(patterns, _) => patterns.iter().flat_map(Pattern::identifiers).collect(),
+            Pattern::Wildcard(_) => vec![],
+        }
     }
 }
 
@@ -67,10 +192,13 @@ impl Identifier {
 #[derive(Debug, Clone)]
 pub enum Expression {
     /// An integer literal.
-    IntegerLiteral(i32),
+    IntegerLiteral(i32, Span),
 
     /// A string literal.
-    StringLiteral(String),
+    StringLiteral(String, Span),
+
+    /// A boolean literal, `true` or `false`.
+    BooleanLiteral(bool, Span),
 
     /// A variable.
     Identifier(Type, Identifier),
@@ -83,8 +211,8 @@ pub enum Expression {
         /// The operator of the binary operation.
         operator: Operator,
 
-        /// The position of the operator in the source code.
-        position: Position,
+        /// The span of the binary operation in the source code, from the start of its left-hand side to the end of its right-hand side.
+        span: Span,
 
         /// The right-hand side of the binary operation.
         right: Box<Expression>,
@@ -95,26 +223,483 @@ pub enum Expression {
 
     /// A function call.
     FunctionCall {
-        /// The identifier of the function.
-        identifier: Identifier,
+        /// The expression being called.
+        /// This is usually an identifier, but can be any expression of a function type.
+        callee: Box<Expression>,
 
         /// The arguments of the function.
-        arguments: Vec<Expression>,
+        /// Once resolved, the arguments are in the order of the function's parameters and are all passed by position,
+        /// including the default values of any parameters that were not given an argument.
+        arguments: Vec<Argument>,
+
+        /// The span of the function call in the source code.
+        span: Span,
 
         /// The return type of the function call.
         r#type: Type,
+
+        /// Whether the call is the value of a return statement, in which case the calling function's frame can be reused.
+        /// This is decided by the resolver, so that recursive functions can make unbounded numbers of tail calls.
+        tail_call: bool,
+    },
+
+    /// A function value, e.g. a lambda such as `|x: Integer| x * 2`.
+    Function(Function),
+
+    /// A list literal, e.g. `[1, 2, 3]`.
+    List {
+        /// The elements of the list.
+        elements: Vec<Expression>,
+
+        /// The span of the list in the source code.
+        span: Span,
+
+        /// The type of the list.
+        r#type: Type,
+    },
+
+    /// A tuple literal, e.g. `(1, "a")`.
+    Tuple {
+        /// The elements of the tuple.
+        elements: Vec<Expression>,
+
+        /// The span of the tuple in the source code.
+        span: Span,
+
+        /// The type of the tuple.
+        r#type: Type,
     },
+
+    /// An access of a tuple's element, e.g. `pair.0`.
+    TupleAccess {
+        /// The tuple being accessed.
+        value: Box<Expression>,
+
+        /// The index of the element.
+        index: usize,
+
+        /// The span of the access in the source code.
+        span: Span,
+
+        /// The type of the element.
+        r#type: Type,
+    },
+
+    /// An index into a list, e.g. `xs[0]`.
+    Index {
+        /// The list being indexed.
+        value: Box<Expression>,
+
+        /// The index of the element.
+        index: Box<Expression>,
+
+        /// The span of the access in the source code.
+        span: Span,
+
+        /// The type of the element.
+        r#type: Type,
+    },
+
+    /// A struct literal, e.g. `Pair { first: 1, second: "a" }`.
+    StructLiteral {
+        /// The name of the struct.
+        identifier: Identifier,
+
+        /// The values of the struct's fields.
+        fields: Vec<(Identifier, Expression)>,
+
+        /// The span of the struct literal in the source code.
+        span: Span,
+
+        /// The type of the struct.
+        r#type: Type,
+    },
+
+    /// A check of whether a value is of the target type, e.g. `x is Integer`.
+    /// Within the branch of an if statement that it guards, the type of a checked variable is narrowed to the target type.
+    Is {
+        /// The value being checked.
+        value: Box<Expression>,
+
+        /// The type that the value is checked against.
+        target: Type,
+
+        /// The span of the check in the source code.
+        span: Span,
+    },
+
+    /// A conversion of a value to the target type, e.g. `x as Integer`, which fails at runtime if the value is not of that type.
+    /// The resolver also inserts casts where a value of type `Any` is used as a more specific type.
+    Cast {
+        /// The value being converted.
+        value: Box<Expression>,
+
+        /// The type that the value is converted to.
+        target: Type,
+
+        /// The span of the cast in the source code.
+        span: Span,
+    },
+
+    /// A call of a method on a value, e.g. `name.length()`.
+    MethodCall {
+        /// The value that the method is called on.
+        value: Box<Expression>,
+
+        /// The name of the method.
+        method: Identifier,
+
+        /// The arguments of the method, excluding the value it is called on.
+        arguments: Vec<Expression>,
+
+        /// The trait that declares the method, if it is a trait method.
+        /// This is filled in by the resolver, so that the interpreter calls the same method that was type checked.
+        r#trait: Option<String>,
+
+        /// The span of the method call in the source code.
+        span: Span,
+
+        /// The return type of the method call.
+        r#type: Type,
+    },
+
+    /// An access of a struct's field, e.g. `pair.first`.
+    FieldAccess {
+        /// The struct being accessed.
+        value: Box<Expression>,
+
+        /// The name of the field.
+        field: Identifier,
+
+        /// The span of the access in the source code.
+        span: Span,
+
+        /// The type of the field.
+        r#type: Type,
+    },
+}
+
+/// A function, which captures the variables in scope where it is declared.
+#[derive(Debug, Clone)]
+pub struct Function {
+    /// The name of the function, if it was declared with `fn`.
+    pub identifier: Option<Identifier>,
+
+    /// The function's type parameters, e.g. `T` in `fn first<T>(xs: List<T>): T`.
+    pub type_parameters: Vec<TypeParameter>,
+
+    /// The parameters of the function.
+    pub parameters: Vec<Parameter>,
+
+    /// The declared return type of the function.
+    pub return_type: Type,
+
+    /// The statements in the body of the function.
+    /// The body of a lambda with an expression body is a single return statement.
+    pub body: Vec<Statement>,
+
+    /// The span of the function in the source code.
+    pub span: Span,
+}
+
+impl Function {
+    /// Returns the type of the function.
+    /// The type of a function with type parameters is generic over them.
+    pub fn r#type(&self) -> Type {
+        let parameters = self.parameters.iter().map(|it| it.r#type.clone()).collect();
+        let r#type = Type::Function(parameters, Box::new(self.return_type.clone()));
+
+        if self.type_parameters.is_empty() {
+            r#type
+        } else {
+            let type_parameters = self.type_parameters.iter().map(TypeParameter::parameter).collect();
+            Type::Generic(type_parameters, Box::new(r#type))
+        }
+    }
+
+    /// Returns the name of the function, used when referring to it in messages.
+    pub fn name(&self) -> &str {
+        self.identifier.as_ref().map_or("<lambda>", |it| it.name.as_str())
+    }
+}
+
+/// A struct, which groups together named values.
+#[derive(Debug, Clone)]
+pub struct Struct {
+    /// The name of the struct.
+    pub identifier: Identifier,
+
+    /// The struct's type parameters.
+    pub type_parameters: Vec<TypeParameter>,
+
+    /// The fields of the struct, in the order that they were declared.
+    pub fields: Vec<Field>,
+
+    /// The span of the struct in the source code.
+    pub span: Span,
+}
+
+/// A field of a struct.
+#[derive(Debug, Clone)]
+pub struct Field {
+    /// The name of the field.
+    pub identifier: Identifier,
+
+    /// The declared type of the field.
+    pub r#type: Type,
+}
+
+/// A newtype, which wraps a value of an existing type in a distinct type.
+/// Values are created by calling the newtype like a function, e.g. `Meters(5)`, and unwrapped with `.value`.
+#[derive(Debug, Clone)]
+pub struct Newtype {
+    /// The name of the newtype.
+    pub identifier: Identifier,
+
+    /// The type of the wrapped value.
+    pub r#type: Type,
+
+    /// The span of the newtype in the source code.
+    pub span: Span,
+}
+
+impl Newtype {
+    /// Returns the struct that represents the newtype, which has a single field called `value`.
+    pub fn r#struct(&self) -> Struct {
+        Struct {
+            identifier: self.identifier.clone(),
+            type_parameters: vec![],
+            fields: vec![Field {
+                identifier: self.value(),
+                r#type: self.r#type.clone(),
+            }],
+            span: self.span,
+        }
+    }
+
+    /// Returns the function that creates a value of the newtype, e.g. `fn Meters(value: Integer) { return Meters { value: value } }`.
+    pub fn constructor(&self) -> Function {
+        let span = self.span;
+        let value = Expression::Identifier(Type::default(), self.value());
+
+        Function {
+            identifier: Some(self.identifier.clone()),
+            type_parameters: vec![],
+            parameters: vec![Parameter {
+                pattern: Pattern::Identifier(self.value()),
+                r#type: self.r#type.clone(),
+                default: None,
+                variadic: false,
+            }],
+            return_type: Type::Named(self.identifier.name.clone(), vec![], self.identifier.span),
+            body: vec![Statement::Return {
+                value: Some(Expression::StructLiteral {
+                    identifier: self.identifier.clone(),
+                    fields: vec![(self.value(), value)],
+                    span,
+                    r#type: Type::default(),
+                }),
+                span,
+            }],
+            span,
+        }
+    }
+
+    /// The name of the newtype's only field.
+    fn value(&self) -> Identifier {
+        Identifier::new("value".to_string(), self.identifier.span)
+    }
+}
+
+/// A trait, which declares methods that types can implement.
+#[derive(Debug, Clone)]
+pub struct Trait {
+    /// The name of the trait.
+    pub identifier: Identifier,
+
+    /// The signatures of the trait's methods.
+    pub methods: Vec<Signature>,
+
+    /// The span of the trait in the source code.
+    pub span: Span,
+}
+
+/// The signature of a method declared by a trait.
+/// The first parameter is always `self`, whose type is the type implementing the trait.
+#[derive(Debug, Clone)]
+pub struct Signature {
+    /// The name of the method.
+    pub identifier: Identifier,
+
+    /// The parameters of the method, including `self`.
+    pub parameters: Vec<Parameter>,
+
+    /// The declared return type of the method.
+    pub return_type: Type,
+}
+
+impl Signature {
+    /// Returns the type of the method.
+    pub fn r#type(&self) -> Type {
+        let parameters = self.parameters.iter().map(|it| it.r#type.clone()).collect();
+        Type::Function(parameters, Box::new(self.return_type.clone()))
+    }
+}
+
+/// An implementation of a trait's methods for a type, or of a struct's own methods.
+#[derive(Debug, Clone)]
+pub struct Implementation {
+    /// The type parameters of the implementation, e.g. `T` in `impl<T: Printable> Printable for Box<T>`.
+    pub type_parameters: Vec<TypeParameter>,
+
+    /// The name of the trait being implemented, or `None` if the methods belong to the type itself.
+    pub r#trait: Option<Identifier>,
+
+    /// The type the trait is implemented for.
+    pub r#type: Type,
+
+    /// The implementations of the trait's methods.
+    pub methods: Vec<Function>,
+
+    /// The span of the implementation in the source code.
+    pub span: Span,
+}
+
+/// A type parameter of a declaration, along with the traits it must implement.
+#[derive(Debug, Clone)]
+pub struct TypeParameter {
+    /// The name of the type parameter.
+    pub identifier: Identifier,
+
+    /// The traits that the type parameter must implement, e.g. `Printable` in `<T: Printable>`.
+    pub bounds: Vec<Identifier>,
+}
+
+impl TypeParameter {
+    /// Returns the type parameter as it appears in the type of the declaration.
+    pub fn parameter(&self) -> GenericParameter {
+        GenericParameter::new(
+            self.identifier.name.clone(),
+            self.bounds.iter().map(|it| it.name.clone()).collect(),
+        )
+    }
+}
+
+/// A parameter of a function.
+#[derive(Debug, Clone)]
+pub struct Parameter {
+    /// The pattern that the argument is matched against, which is usually just the name of the parameter.
+    pub pattern: Pattern,
+
+    /// The declared type of the parameter.
+    pub r#type: Type,
+
+    /// The value of the parameter when the function is called without an argument for it, e.g. `2` in `y: Integer = 2`.
+    /// Default values must be constants, so that they can be evaluated where the function is called.
+    pub default: Option<Expression>,
+
+    /// Whether the parameter is variadic, e.g. `...xs: List<Integer>`, in which case it must be the last parameter.
+    /// It is a list of every argument passed by position after those of the other parameters.
+    pub variadic: bool,
+}
+
+/// An argument of a function call, which is either passed by position or by the name of its parameter, e.g. `y: 3`.
+#[derive(Debug, Clone)]
+pub struct Argument {
+    /// The name of the parameter the argument is passed to, or `None` if it is passed by position.
+    pub name: Option<Identifier>,
+
+    /// The value of the argument.
+    pub value: Expression,
+}
+
+impl Argument {
+    /// Creates an argument which is passed by position.
+    pub fn positional(value: Expression) -> Self {
+        Self { name: None, value }
+    }
 }
 
 impl Expression {
+    /// Whether the expression is a literal, which is a value that is known before the program runs.
+    pub fn is_literal(&self) -> bool {
+        matches!(
+            self,
+            Expression::IntegerLiteral(..) | Expression::StringLiteral(..) | Expression::BooleanLiteral(..)
+        )
+    }
+
+    /// Whether the expression refers to a place that a value can be stored in,
+    /// which is a variable, or a field or element of a place.
+    pub fn is_place(&self) -> bool {
+        match self {
+            Expression::Identifier(..) => true,
+            Expression::FieldAccess { value, .. }
+            | Expression::TupleAccess { value, .. }
+            | Expression::Index { value, .. } => value.is_place(),
+            _ => false,
+        }
+    }
+
     /// Returns the type of the expression.
     pub fn r#type(&self) -> Type {
         match self {
-            Self::IntegerLiteral(_) => Type::Integer,
-            Self::StringLiteral(_) => Type::String,
+            Self::IntegerLiteral(..) => Type::Integer,
+            Self::StringLiteral(..) => Type::String,
+            Self::BooleanLiteral(..) => Type::Boolean,
+            Self::Is { .. } => Type::Boolean,
+            Self::Cast { target, .. } => target.clone(),
             Self::Identifier(r#type, _) => r#type.clone(),
             Self::BinaryOperation { r#type, .. } => r#type.clone(),
             Self::FunctionCall { r#type, .. } => r#type.clone(),
+            Self::Function(function) => function.r#type(),
+            Self::List { r#type, .. } => r#type.clone(),
+            Self::Tuple { r#type, .. } => r#type.clone(),
+            Self::TupleAccess { r#type, .. } => r#type.clone(),
+            Self::Index { r#type, .. } => r#type.clone(),
+            Self::StructLiteral { r#type, .. } => r#type.clone(),
+            Self::MethodCall { r#type, .. } => r#type.clone(),
+            Self::FieldAccess { r#type, .. } => r#type.clone(),
+        }
+    }
+
+    /// Returns the span of the whole expression in the source code.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::IntegerLiteral(_, span) | Self::StringLiteral(_, span) | Self::BooleanLiteral(_, span) => *span,
+            Self::Identifier(_, identifier) => identifier.span,
+            Self::Function(function) => function.span,
+            Self::BinaryOperation { span, .. }
+            | Self::FunctionCall { span, .. }
+            | Self::List { span, .. }
+            | Self::Tuple { span, .. }
+            | Self::TupleAccess { span, .. }
+            | Self::Index { span, .. }
+            | Self::StructLiteral { span, .. }
+            | Self::Is { span, .. }
+            | Self::Cast { span, .. }
+            | Self::MethodCall { span, .. }
+            | Self::FieldAccess { span, .. } => *span,
+        }
+    }
+}
+
+impl Statement {
+    /// Returns the span of the whole statement in the source code.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Let { span, .. }
+            | Self::Assignment { span, .. }
+            | Self::Constant { span, .. }
+            | Self::Return { span, .. }
+            | Self::If { span, .. }
+            | Self::TypeAlias { span, .. } => *span,
+            Self::Function(function) => function.span,
+            Self::Struct(r#struct) => r#struct.span,
+            Self::Newtype(newtype) => newtype.span,
+            Self::Trait(r#trait) => r#trait.span,
+            Self::Implementation(implementation) => implementation.span,
+            Self::Expression(expression) => expression.span(),
         }
     }
 }
@@ -127,6 +712,30 @@ pub enum Operator {
     Divide,
 }
 
+impl std::fmt::Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Operator::Add => "+",
+            Operator::Subtract => "-",
+            Operator::Multiply => "*",
+            Operator::Divide => "/",
+        };
+
+        write!(f, "{}", symbol)
+    }
+}
+
+impl Operator {
+    /// How tightly the operator binds to its operands, where a higher precedence binds more tightly.
+    /// Multiplication and division are applied before addition and subtraction.
+    pub fn precedence(self) -> u8 {
+        match self {
+            Operator::Add | Operator::Subtract => 1,
+            Operator::Multiply | Operator::Divide => 2,
+        }
+    }
+}
+
 impl From<TokenType> for Option<Operator> {
     fn from(val: TokenType) -> Self {
         let operator = match val {
@@ -140,3 +749,261 @@ impl From<TokenType> for Option<Operator> {
         Some(operator)
     }
 }
+
+/// Whether a block of statements always returns, which is the case if any of its statements is a return statement,
+/// or an if statement whose branches both always return.
+pub fn always_returns(statements: &[Statement]) -> bool {
+    statements.iter().any(|statement| match statement {
+        Statement::Return { .. } => true,
+
+        Statement::If {
+            then_branch,
+            else_branch: Some(else_branch),
+            ..
+        } => always_returns(then_branch) && always_returns(else_branch),
+
+        _ => false,
+    })
+}
+
+impl Statement {
+    /// Replaces every type in this statement, including in any nested statements and expressions, using [map].
+    pub fn map_types(self, map: &mut impl FnMut(Type) -> Type) -> Statement {
+        match self {
+            Statement::Let {
+                pattern,
+                value,
+                r#type,
+                type_span,
+                mutable,
+                span,
+            } => Statement::Let {
+                pattern,
+                value: value.map_types(map),
+                r#type: map(r#type),
+                type_span,
+                mutable,
+                span,
+            },
+
+            Statement::Assignment { place, value, span } => Statement::Assignment {
+                place: place.map_types(map),
+                value: value.map_types(map),
+                span,
+            },
+
+            Statement::Constant {
+                identifier,
+                value,
+                span,
+            } => Statement::Constant {
+                identifier,
+                value: value.map_types(map),
+                span,
+            },
+
+            Statement::Return { value, span } => Statement::Return {
+                value: value.map(|it| it.map_types(map)),
+                span,
+            },
+
+            Statement::If {
+                condition,
+                then_branch,
+                else_branch,
+                span,
+            } => Statement::If {
+                condition: condition.map_types(map),
+                then_branch: then_branch.into_iter().map(|it| it.map_types(map)).collect(),
+                else_branch: else_branch.map(|branch| branch.into_iter().map(|it| it.map_types(map)).collect()),
+                span,
+            },
+
+            Statement::Function(function) => Statement::Function(function.map_types(map)),
+
+            Statement::TypeAlias {
+                identifier,
+                r#type,
+                span,
+            } => Statement::TypeAlias {
+                identifier,
+                r#type: map(r#type),
+                span,
+            },
+
+            Statement::Newtype(newtype) => Statement::Newtype(Newtype {
+                r#type: map(newtype.r#type),
+                ..newtype
+            }),
+
+            Statement::Struct(r#struct) => Statement::Struct(r#struct),
+
+            Statement::Trait(r#trait) => Statement::Trait(r#trait),
+
+            Statement::Implementation(implementation) => Statement::Implementation(Implementation {
+                methods: implementation.methods.into_iter().map(|it| it.map_types(map)).collect(),
+                ..implementation
+            }),
+
+            Statement::Expression(expression) => Statement::Expression(expression.map_types(map)),
+        }
+    }
+}
+
+impl Expression {
+    /// Replaces every type in this expression, including in any nested statements and expressions, using [map].
+    pub fn map_types(self, map: &mut impl FnMut(Type) -> Type) -> Expression {
+        match self {
+            Expression::IntegerLiteral(..) | Expression::StringLiteral(..) | Expression::BooleanLiteral(..) => self,
+
+            Expression::Is { value, target, span } => Expression::Is {
+                value: Box::new(value.map_types(map)),
+                target: map(target),
+                span,
+            },
+
+            Expression::Cast { value, target, span } => Expression::Cast {
+                value: Box::new(value.map_types(map)),
+                target: map(target),
+                span,
+            },
+
+            Expression::Identifier(r#type, identifier) => Expression::Identifier(map(r#type), identifier),
+
+            Expression::BinaryOperation {
+                left,
+                operator,
+                span,
+                right,
+                r#type,
+            } => Expression::BinaryOperation {
+                left: Box::new(left.map_types(map)),
+                operator,
+                span,
+                right: Box::new(right.map_types(map)),
+                r#type: map(r#type),
+            },
+
+            Expression::FunctionCall {
+                callee,
+                arguments,
+                span,
+                r#type,
+                tail_call,
+            } => Expression::FunctionCall {
+                callee: Box::new(callee.map_types(map)),
+                arguments: arguments
+                    .into_iter()
+                    .map(|it| Argument {
+                        name: it.name,
+                        value: it.value.map_types(map),
+                    })
+                    .collect(),
+                span,
+                r#type: map(r#type),
+                tail_call,
+            },
+
+            Expression::Function(function) => Expression::Function(function.map_types(map)),
+
+            Expression::List { elements, span, r#type } => Expression::List {
+                elements: elements.into_iter().map(|it| it.map_types(map)).collect(),
+                span,
+                r#type: map(r#type),
+            },
+
+            Expression::Tuple { elements, span, r#type } => Expression::Tuple {
+                elements: elements.into_iter().map(|it| it.map_types(map)).collect(),
+                span,
+                r#type: map(r#type),
+            },
+
+            Expression::TupleAccess {
+                value,
+                index,
+                span,
+                r#type,
+            } => Expression::TupleAccess {
+                value: Box::new(value.map_types(map)),
+                index,
+                span,
+                r#type: map(r#type),
+            },
+
+            Expression::Index {
+                value,
+                index,
+                span,
+                r#type,
+            } => Expression::Index {
+                value: Box::new(value.map_types(map)),
+                index: Box::new(index.map_types(map)),
+                span,
+                r#type: map(r#type),
+            },
+
+            Expression::StructLiteral {
+                identifier,
+                fields,
+                span,
+                r#type,
+            } => Expression::StructLiteral {
+                identifier,
+                fields: fields.into_iter().map(|(field, value)| (field, value.map_types(map))).collect(),
+                span,
+                r#type: map(r#type),
+            },
+
+            Expression::MethodCall {
+                value,
+                method,
+                arguments,
+                r#trait,
+                span,
+                r#type,
+            } => Expression::MethodCall {
+                value: Box::new(value.map_types(map)),
+                method,
+                arguments: arguments.into_iter().map(|it| it.map_types(map)).collect(),
+                r#trait,
+                span,
+                r#type: map(r#type),
+            },
+
+            Expression::FieldAccess {
+                value,
+                field,
+                span,
+                r#type,
+            } => Expression::FieldAccess {
+                value: Box::new(value.map_types(map)),
+                field,
+                span,
+                r#type: map(r#type),
+            },
+        }
+    }
+}
+
+impl Function {
+    /// Replaces every type in this function's signature and body using [map].
+    pub fn map_types(self, map: &mut impl FnMut(Type) -> Type) -> Function {
+        Function {
+            identifier: self.identifier,
+            type_parameters: self.type_parameters,
+            parameters: self
+                .parameters
+                .into_iter()
+                .map(|parameter| Parameter {
+                    pattern: parameter.pattern,
+                    r#type: map(parameter.r#type),
+                    default: parameter.default.map(|it| it.map_types(map)),
+                    variadic: parameter.variadic,
+                })
+                .collect(),
+            return_type: map(self.return_type),
+            body: self.body.into_iter().map(|it| it.map_types(map)).collect(),
+            span: self.span,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+/// Returns the explanation of the error with the given [code], e.g. `C0013`, or `None` if there is no such error.
+/// Each explanation describes when the error occurs, with an example that causes it and the same example fixed.
+pub fn explanation(code: &str) -> Option<&'static str> {
+    let explanation = match code {
+        "C0001" => include_str!("explanations/C0001.md"),
+        "C0002" => include_str!("explanations/C0002.md"),
+        "C0003" => include_str!("explanations/C0003.md"),
+        "C0004" => include_str!("explanations/C0004.md"),
+        "C0005" => include_str!("explanations/C0005.md"),
+        "C0006" => include_str!("explanations/C0006.md"),
+        "C0007" => include_str!("explanations/C0007.md"),
+        "C0008" => include_str!("explanations/C0008.md"),
+        "C0009" => include_str!("explanations/C0009.md"),
+        "C0010" => include_str!("explanations/C0010.md"),
+        "C0011" => include_str!("explanations/C0011.md"),
+        "C0012" => include_str!("explanations/C0012.md"),
+        "C0013" => include_str!("explanations/C0013.md"),
+        "C0014" => include_str!("explanations/C0014.md"),
+        "C0015" => include_str!("explanations/C0015.md"),
+        "C0016" => include_str!("explanations/C0016.md"),
+        "C0017" => include_str!("explanations/C0017.md"),
+        "C0018" => include_str!("explanations/C0018.md"),
+        "C0019" => include_str!("explanations/C0019.md"),
+        "C0020" => include_str!("explanations/C0020.md"),
+        "C0021" => include_str!("explanations/C0021.md"),
+        "C0022" => include_str!("explanations/C0022.md"),
+        "C0023" => include_str!("explanations/C0023.md"),
+        "C0024" => include_str!("explanations/C0024.md"),
+        "C0025" => include_str!("explanations/C0025.md"),
+        "C0026" => include_str!("explanations/C0026.md"),
+        "C0027" => include_str!("explanations/C0027.md"),
+        "C0028" => include_str!("explanations/C0028.md"),
+        "C0029" => include_str!("explanations/C0029.md"),
+        "C0030" => include_str!("explanations/C0030.md"),
+        "C0031" => include_str!("explanations/C0031.md"),
+        "C0032" => include_str!("explanations/C0032.md"),
+        "C0033" => include_str!("explanations/C0033.md"),
+        "C0034" => include_str!("explanations/C0034.md"),
+        "C0035" => include_str!("explanations/C0035.md"),
+        "C0036" => include_str!("explanations/C0036.md"),
+        "C0037" => include_str!("explanations/C0037.md"),
+        "C0038" => include_str!("explanations/C0038.md"),
+        "C0039" => include_str!("explanations/C0039.md"),
+        "C0040" => include_str!("explanations/C0040.md"),
+        "C0041" => include_str!("explanations/C0041.md"),
+        "C0042" => include_str!("explanations/C0042.md"),
+        "C0043" => include_str!("explanations/C0043.md"),
+        "C0044" => include_str!("explanations/C0044.md"),
+        "C0045" => include_str!("explanations/C0045.md"),
+        "C0046" => include_str!("explanations/C0046.md"),
+        "C0047" => include_str!("explanations/C0047.md"),
+        "C0048" => include_str!("explanations/C0048.md"),
+        "C0049" => include_str!("explanations/C0049.md"),
+        "C0050" => include_str!("explanations/C0050.md"),
+        "C0051" => include_str!("explanations/C0051.md"),
+        "C0052" => include_str!("explanations/C0052.md"),
+        "C0053" => include_str!("explanations/C0053.md"),
+        "C0054" => include_str!("explanations/C0054.md"),
+        "C0055" => include_str!("explanations/C0055.md"),
+        "C0056" => include_str!("explanations/C0056.md"),
+        "C0057" => include_str!("explanations/C0057.md"),
+        "C0058" => include_str!("explanations/C0058.md"),
+        "C0059" => include_str!("explanations/C0059.md"),
+        "C0060" => include_str!("explanations/C0060.md"),
+        "C0061" => include_str!("explanations/C0061.md"),
+        "C0062" => include_str!("explanations/C0062.md"),
+        "C0063" => include_str!("explanations/C0063.md"),
+        "C0064" => include_str!("explanations/C0064.md"),
+        "C0065" => include_str!("explanations/C0065.md"),
+        "C0066" => include_str!("explanations/C0066.md"),
+        "C0067" => include_str!("explanations/C0067.md"),
+        "C0068" => include_str!("explanations/C0068.md"),
+        "C0069" => include_str!("explanations/C0069.md"),
+        "C0070" => include_str!("explanations/C0070.md"),
+        "C0071" => include_str!("explanations/C0071.md"),
+        "C0072" => include_str!("explanations/C0072.md"),
+        "C0073" => include_str!("explanations/C0073.md"),
+        "C0074" => include_str!("explanations/C0074.md"),
+        "C0075" => include_str!("explanations/C0075.md"),
+        _ => return None,
+    };
+
+    Some(explanation)
+}
@@ -0,0 +1,48 @@
+use crate::span::Span;
+
+/// Returns a JSON string containing [value], escaping any characters that cannot appear in a JSON string as they are.
+pub fn string(value: &str) -> String {
+    let mut result = String::from('"');
+
+    for char in value.chars() {
+        match char {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            char if char.is_control() => result.push_str(&format!("\\u{:04x}", char as u32)),
+            char => result.push(char),
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+/// Returns a JSON array of [values], which are already written as JSON.
+pub fn array(values: impl IntoIterator<Item = String>) -> String {
+    format!("[{}]", values.into_iter().collect::<Vec<_>>().join(","))
+}
+
+/// Returns a JSON object with the given [fields], whose values are already written as JSON.
+pub fn object(fields: &[(&str, String)]) -> String {
+    let fields = fields.iter().map(|(key, value)| format!("{}:{}", string(key), value));
+    format!("{{{}}}", fields.collect::<Vec<_>>().join(","))
+}
+
+/// Returns a [span] of the [source] code as a JSON object, with both its byte offsets and its lines and columns.
+/// Lines and columns are counted from one, as they are when errors are printed.
+pub fn span(span: Span, source: &str) -> String {
+    let start = span.start_location(source);
+    let end = span.end_location(source);
+
+    object(&[
+        ("start", span.start.to_string()),
+        ("end", span.end.to_string()),
+        ("line_start", (start.line + 1).to_string()),
+        ("column_start", (start.column + 1).to_string()),
+        ("line_end", (end.line + 1).to_string()),
+        ("column_end", (end.column + 1).to_string()),
+    ])
+}
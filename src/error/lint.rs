@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+/// A kind of warning, whose level can be changed with the `-A`, `-W` and `-D` flags,
+/// or which can be allowed for a whole file with a `// cecelia:allow(...)` comment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+    /// A variable which is declared but never used.
+    Unused,
+
+    /// Code which can never run, as it follows a return statement.
+    Unreachable,
+
+    /// A variable which is declared with the same name as a variable from an outer block, hiding it.
+    Shadowing,
+}
+
+impl Lint {
+    /// Every lint, in the order they are listed above.
+    pub const ALL: [Lint; 3] = [Lint::Unused, Lint::Unreachable, Lint::Shadowing];
+
+    /// Returns the name that the lint is referred to by on the command line and in comments.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::Unused => "unused",
+            Lint::Unreachable => "unreachable",
+            Lint::Shadowing => "shadowing",
+        }
+    }
+
+    /// Returns the lint with the given [name], or `None` if there is no such lint.
+    pub fn from_name(name: &str) -> Option<Lint> {
+        Lint::ALL.into_iter().find(|it| it.name() == name)
+    }
+}
+
+/// How a lint is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    /// The lint is not reported at all.
+    Allow,
+
+    /// The lint is reported as a warning, which does not stop the program from running.
+    Warn,
+
+    /// The lint is reported as an error.
+    Deny,
+}
+
+/// The level of each lint, which is a warning unless it is changed.
+pub struct Lints {
+    levels: HashMap<Lint, Level>,
+}
+
+impl Default for Lints {
+    fn default() -> Self {
+        Self {
+            levels: Lint::ALL.into_iter().map(|it| (it, Level::Warn)).collect(),
+        }
+    }
+}
+
+impl Lints {
+    pub fn set(&mut self, lint: Lint, level: Level) {
+        self.levels.insert(lint, level);
+    }
+
+    pub fn level(&self, lint: Lint) -> Level {
+        self.levels.get(&lint).copied().unwrap_or(Level::Warn)
+    }
+}
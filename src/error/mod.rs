@@ -0,0 +1,1053 @@
+pub mod explanation;
+pub mod json;
+pub mod lint;
+
+use std::{fmt::Display, rc::Rc};
+
+use colored::{ColoredString, Colorize};
+
+use crate::{
+    ast::Operator,
+    interpreter::value::{Closure, Value},
+    lexer::TokenType,
+    r#type::Type,
+    span::{Location, Span},
+};
+
+use lint::Lint;
+
+pub struct Error {
+    /// The kind of error, along with the values and types it is about.
+    /// Those can be large, so they are kept on the heap to keep errors small to return.
+    pub error_type: Box<ErrorType>,
+    pub span: Span,
+
+    /// Whether the error stops the program from running, or is only a warning.
+    pub severity: Severity,
+
+    /// The labels, notes and suggestions of the error.
+    /// Most errors have none, so they are only allocated when needed, which keeps errors small to return.
+    pub details: Option<Box<Details>>,
+}
+
+/// How serious an error is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+
+    /// A possible mistake, which does not stop the program from running.
+    Warning,
+}
+
+impl Severity {
+    /// Returns the name that errors of this severity are printed with.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// Everything about an error other than its type and span, which helps to explain it.
+#[derive(Default)]
+pub struct Details {
+    /// Other spans in the source code that help to explain the error, such as where something was declared.
+    pub labels: Vec<Label>,
+
+    /// Further information about why the error occurred.
+    pub notes: Vec<String>,
+
+    /// Suggestions for how to fix the error.
+    pub help: Vec<String>,
+
+    /// The calls that were being made when the error occurred at runtime, from outermost to innermost.
+    pub trace: Vec<Frame>,
+}
+
+/// A call that was being made when a runtime error occurred.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    /// The name of the function or method being called, as it was called.
+    pub function: String,
+
+    /// The span of the call.
+    pub span: Span,
+}
+
+/// A span in the source code that is related to an error, with a message explaining how.
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+pub enum ErrorType {
+    UnexpectedEOF,
+
+    UnexpectedCharacter(char),
+    ExpectedCharacter(char),
+    InvalidNumber(String),
+
+    UnexpectedToken(TokenType),
+    ExpectedToken(TokenType),
+    ExpectedAnyIdentifier,
+    ExpectedEndOfStatement(TokenType),
+
+    UnableToResolveType(String),
+
+    TypeMismatch(Type, Type),
+
+    VariableAlreadyDeclared(String),
+    InvalidBinaryOperation(Value, Operator, Value),
+    Overflow(Value, Operator, Value),
+    DivisionByZero,
+    NotConstant,
+    Return(Option<Value>),
+    TailCall(Rc<Closure>, Vec<Value>),
+    StackOverflow(Option<usize>),
+
+    UnknownVariable(String),
+    UnknownFunction(String),
+    UnableToInferType,
+
+    InvalidNumberOfArguments(usize, usize),
+    NotCallable(Type),
+    MissingReturn(String),
+
+    TypeAlreadyDeclared(String),
+    InvalidNumberOfTypeArguments(String, usize, usize),
+    UnableToInferTypeParameter(String, String),
+    NotIndexable(Type),
+    IndexOutOfBounds(i32, usize),
+    NoTupleElement(Type, usize),
+    CannotDestructure(Type, usize),
+    UnnarrowedUnion(Type),
+    IndistinguishableUnion(Type),
+    ImpossibleTypeCheck(Type, Type),
+    UncheckableType(Type),
+    InvalidCast(Type, Type),
+    UnknownField(Type, String),
+    MissingField(String, String),
+    DuplicateField(String),
+    UnsupportedOperator(Operator, Type),
+
+    UnknownTrait(String),
+    TraitNotImplemented(String, Type),
+    CannotImplement(String, Type),
+    ImplementationAlreadyDeclared(String, Type),
+    MissingMethod(String, String),
+    UnknownMethod(String, String),
+    DuplicateMethod(String),
+    MissingSelfParameter(String),
+
+    NoMethod(Type, String),
+    AmbiguousMethod(Type, String),
+    CannotDeclareMethods(Type),
+    NotAssignable,
+    MissingArgument(String),
+    DuplicateArgument(String),
+    UnknownArgument(String),
+    PositionalAfterNamed,
+    NamedArgumentsNotAllowed,
+    DefaultNotAllowed,
+    DefaultNotConstant,
+    VariadicNotLast,
+    VariadicNotList(Type),
+    VariadicNotAllowed,
+    VariadicArgumentByName(String),
+    ImmutableVariable(String),
+    CapturedVariable(String),
+    CapturedMutableVariable(String),
+    CyclicTypeAlias(String),
+    RecursiveStruct(String),
+    NestedTooDeeply,
+    DuplicateTypeParameter(String),
+
+    UnusedVariable(String),
+    UnreachableCode,
+    ShadowedVariable(String),
+    UnknownLint(String),
+}
+
+impl ErrorType {
+    /// Returns the stable code of this type of error, e.g. `C0013`, whose explanation is shown by `cecelia explain`.
+    /// Codes are never changed or reused, so new types of error are given the next unused code.
+    /// Control flow which is implemented as an error is never reported, so it has no code.
+    pub fn code(&self) -> Option<&'static str> {
+        let code = match self {
+            ErrorType::UnexpectedEOF => "C0001",
+            ErrorType::UnexpectedCharacter(..) => "C0002",
+            ErrorType::ExpectedCharacter(..) => "C0003",
+            ErrorType::InvalidNumber(..) => "C0004",
+            ErrorType::UnexpectedToken(..) => "C0005",
+            ErrorType::ExpectedToken(..) => "C0006",
+            ErrorType::ExpectedAnyIdentifier => "C0007",
+            ErrorType::ExpectedEndOfStatement(..) => "C0009",
+            ErrorType::UnableToResolveType(..) => "C0012",
+            ErrorType::TypeMismatch(..) => "C0013",
+            ErrorType::VariableAlreadyDeclared(..) => "C0014",
+            ErrorType::InvalidBinaryOperation(..) => "C0015",
+            ErrorType::Overflow(..) => "C0016",
+            ErrorType::DivisionByZero => "C0017",
+            ErrorType::NotConstant => "C0018",
+            ErrorType::StackOverflow(..) => "C0019",
+            ErrorType::UnknownVariable(..) => "C0020",
+            ErrorType::UnknownFunction(..) => "C0021",
+            ErrorType::UnableToInferType => "C0022",
+            ErrorType::InvalidNumberOfArguments(..) => "C0023",
+            ErrorType::NotCallable(..) => "C0024",
+            ErrorType::MissingReturn(..) => "C0025",
+            ErrorType::TypeAlreadyDeclared(..) => "C0026",
+            ErrorType::InvalidNumberOfTypeArguments(..) => "C0027",
+            ErrorType::UnableToInferTypeParameter(..) => "C0028",
+            ErrorType::NotIndexable(..) => "C0029",
+            ErrorType::IndexOutOfBounds(..) => "C0030",
+            ErrorType::NoTupleElement(..) => "C0031",
+            ErrorType::CannotDestructure(..) => "C0032",
+            ErrorType::UnnarrowedUnion(..) => "C0033",
+            ErrorType::IndistinguishableUnion(..) => "C0034",
+            ErrorType::ImpossibleTypeCheck(..) => "C0035",
+            ErrorType::UncheckableType(..) => "C0036",
+            ErrorType::InvalidCast(..) => "C0037",
+            ErrorType::UnknownField(..) => "C0038",
+            ErrorType::MissingField(..) => "C0039",
+            ErrorType::DuplicateField(..) => "C0040",
+            ErrorType::UnsupportedOperator(..) => "C0041",
+            ErrorType::UnknownTrait(..) => "C0042",
+            ErrorType::TraitNotImplemented(..) => "C0043",
+            ErrorType::CannotImplement(..) => "C0044",
+            ErrorType::ImplementationAlreadyDeclared(..) => "C0045",
+            ErrorType::MissingMethod(..) => "C0046",
+            ErrorType::UnknownMethod(..) => "C0047",
+            ErrorType::DuplicateMethod(..) => "C0048",
+            ErrorType::MissingSelfParameter(..) => "C0049",
+            ErrorType::NoMethod(..) => "C0050",
+            ErrorType::AmbiguousMethod(..) => "C0051",
+            ErrorType::CannotDeclareMethods(..) => "C0052",
+            ErrorType::NotAssignable => "C0053",
+            ErrorType::MissingArgument(..) => "C0054",
+            ErrorType::DuplicateArgument(..) => "C0055",
+            ErrorType::UnknownArgument(..) => "C0056",
+            ErrorType::PositionalAfterNamed => "C0057",
+            ErrorType::NamedArgumentsNotAllowed => "C0058",
+            ErrorType::DefaultNotAllowed => "C0059",
+            ErrorType::DefaultNotConstant => "C0060",
+            ErrorType::VariadicNotLast => "C0061",
+            ErrorType::VariadicNotList(..) => "C0062",
+            ErrorType::VariadicNotAllowed => "C0063",
+            ErrorType::VariadicArgumentByName(..) => "C0064",
+            ErrorType::ImmutableVariable(..) => "C0065",
+            ErrorType::CapturedVariable(..) => "C0066",
+            ErrorType::CapturedMutableVariable(..) => "C0071",
+            ErrorType::CyclicTypeAlias(..) => "C0072",
+            ErrorType::RecursiveStruct(..) => "C0073",
+            ErrorType::NestedTooDeeply => "C0074",
+            ErrorType::DuplicateTypeParameter(..) => "C0075",
+            ErrorType::UnusedVariable(..) => "C0067",
+            ErrorType::UnreachableCode => "C0068",
+            ErrorType::ShadowedVariable(..) => "C0069",
+            ErrorType::UnknownLint(..) => "C0070",
+            ErrorType::Return(_) | ErrorType::TailCall(..) => return None,
+        };
+
+        Some(code)
+    }
+
+    /// Returns the lint that this type of error is a warning for, or `None` if it is always an error.
+    pub fn lint(&self) -> Option<Lint> {
+        match self {
+            ErrorType::UnusedVariable(_) => Some(Lint::Unused),
+            ErrorType::UnreachableCode => Some(Lint::Unreachable),
+            ErrorType::ShadowedVariable(_) => Some(Lint::Shadowing),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ErrorType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorType::UnexpectedCharacter(char) => {
+                // Control characters are escaped, as they would otherwise be invisible or mess up the terminal.
+                write!(f, "Unexpected character: {}", char.escape_debug())
+            },
+
+            ErrorType::InvalidNumber(string) => {
+                write!(f, "Invalid number: `{}`", string)
+            },
+
+            ErrorType::ExpectedCharacter(char) => {
+                write!(f, "Expected character: {}", char)
+            },
+
+            ErrorType::UnexpectedToken(token) => {
+                write!(f, "Unexpected token: {:?}", token)
+            },
+
+            ErrorType::ExpectedToken(token) => {
+                write!(f, "Expected token: {:?}", token)
+            },
+
+            ErrorType::ExpectedAnyIdentifier => {
+                write!(f, "Expected any identifier")
+            },
+
+            ErrorType::ExpectedEndOfStatement(token) => {
+                write!(f, "Expected end of statement, but found: {:?}", token)
+            },
+
+            ErrorType::TypeMismatch(expected, actual) => {
+                write!(f, "Type mismatch: `{}` and `{}`", expected, actual)
+            },
+
+            ErrorType::UnableToResolveType(type_name) => {
+                write!(f, "Unable to resolve type: `{}`", type_name)
+            },
+
+            ErrorType::UnexpectedEOF => write!(f, "Unexpected EOF"),
+
+            ErrorType::VariableAlreadyDeclared(name) => {
+                write!(f, "Variable already declared: `{}`", name)
+            },
+
+            ErrorType::InvalidBinaryOperation(left, operator, right) => {
+                write!(
+                    f,
+                    "Invalid binary operation: `{:?}` `{:?}` `{:?}`",
+                    left, operator, right
+                )
+            },
+
+            ErrorType::Overflow(left, operator, right) => {
+                write!(f, "`{} {} {}` is too large to fit in an integer", left, operator, right)
+            },
+
+            ErrorType::DivisionByZero => {
+                write!(f, "Division by zero")
+            },
+
+            ErrorType::NotConstant => {
+                write!(
+                    f,
+                    "The value of a constant must be a literal, another constant, or an operation on them"
+                )
+            },
+
+            ErrorType::Return(_) | ErrorType::TailCall(..) => {
+                write!(f, "INTERAL WORKAROUND")
+            },
+
+            ErrorType::StackOverflow(Some(limit)) => {
+                write!(
+                    f,
+                    "Stack overflow: more than {} function calls are nested inside each other",
+                    limit
+                )
+            },
+
+            ErrorType::StackOverflow(None) => {
+                write!(
+                    f,
+                    "Stack overflow: function calls and expressions are nested too deeply"
+                )
+            },
+
+            ErrorType::UnknownVariable(name) => {
+                write!(f, "`{}` has not been declared yet.", name)
+            },
+
+            ErrorType::UnknownFunction(name) => {
+                write!(f, "The function `{}` has not been declared yet.", name)
+            },
+
+            ErrorType::UnableToInferType => {
+                write!(f, "Unable to infer type, consider adding a type annotation")
+            },
+
+            ErrorType::InvalidNumberOfArguments(expected, actual) => {
+                write!(
+                    f,
+                    "Invalid number of arguments: expected {} but got {}",
+                    expected, actual
+                )
+            },
+
+            ErrorType::NotCallable(r#type) => {
+                write!(f, "A value of type `{}` cannot be called", r#type)
+            },
+
+            ErrorType::MissingReturn(name) => {
+                write!(f, "The function `{}` must end with a return statement", name)
+            },
+
+            ErrorType::TypeAlreadyDeclared(name) => {
+                write!(f, "Type already declared: `{}`", name)
+            },
+
+            ErrorType::InvalidNumberOfTypeArguments(name, expected, actual) => {
+                write!(
+                    f,
+                    "`{}` takes {} type argument(s) but {} were given",
+                    name, expected, actual
+                )
+            },
+
+            ErrorType::UnableToInferTypeParameter(parameter, name) => {
+                write!(
+                    f,
+                    "Unable to infer type parameter `{}` of `{}`, consider adding a type annotation",
+                    parameter, name
+                )
+            },
+
+            ErrorType::NotIndexable(r#type) => {
+                write!(f, "A value of type `{}` cannot be indexed", r#type)
+            },
+
+            ErrorType::IndexOutOfBounds(index, length) => {
+                write!(f, "Index {} is out of bounds for a list of length {}", index, length)
+            },
+
+            ErrorType::NoTupleElement(r#type, index) => {
+                write!(f, "`{}` has no element `{}`", r#type, index)
+            },
+
+            ErrorType::UnnarrowedUnion(r#type) => {
+                write!(
+                    f,
+                    "A value of type `{}` could be any of its members, check which one it is with `is` first",
+                    r#type
+                )
+            },
+
+            ErrorType::IndistinguishableUnion(r#type) => {
+                write!(
+                    f,
+                    "The members of `{}` cannot be told apart at runtime, as they are not distinct types",
+                    r#type
+                )
+            },
+
+            ErrorType::ImpossibleTypeCheck(r#type, target) => {
+                write!(f, "A value of type `{}` can never be a `{}`", r#type, target)
+            },
+
+            ErrorType::UncheckableType(r#type) => {
+                write!(
+                    f,
+                    "Whether a value is a `{}` cannot be checked when the program runs",
+                    r#type
+                )
+            },
+
+            ErrorType::InvalidCast(r#type, target) => {
+                write!(
+                    f,
+                    "Expected a value of type `{}`, but found a value of type `{}`",
+                    target, r#type
+                )
+            },
+
+            ErrorType::CannotDestructure(r#type, count) => {
+                write!(
+                    f,
+                    "A value of type `{}` cannot be destructured into {} elements",
+                    r#type, count
+                )
+            },
+
+            ErrorType::UnknownField(r#type, field) => {
+                write!(f, "`{}` has no field named `{}`", r#type, field)
+            },
+
+            ErrorType::MissingField(name, field) => {
+                write!(f, "Missing field `{}` in `{}`", field, name)
+            },
+
+            ErrorType::DuplicateField(field) => {
+                write!(f, "Field `{}` was specified more than once", field)
+            },
+
+            ErrorType::UnsupportedOperator(operator, r#type) => {
+                write!(
+                    f,
+                    "The operator `{}` cannot be applied to values of type `{}`",
+                    operator, r#type
+                )
+            },
+
+            ErrorType::UnknownTrait(name) => {
+                write!(f, "The trait `{}` has not been declared yet.", name)
+            },
+
+            ErrorType::TraitNotImplemented(r#trait, r#type) => {
+                write!(f, "`{}` does not implement the trait `{}`", r#type, r#trait)
+            },
+
+            ErrorType::CannotImplement(r#trait, r#type) => {
+                write!(f, "The trait `{}` cannot be implemented for `{}`", r#trait, r#type)
+            },
+
+            ErrorType::ImplementationAlreadyDeclared(r#trait, r#type) => {
+                write!(f, "The trait `{}` is already implemented for `{}`", r#trait, r#type)
+            },
+
+            ErrorType::MissingMethod(r#trait, method) => {
+                write!(f, "Missing method `{}` of the trait `{}`", method, r#trait)
+            },
+
+            ErrorType::UnknownMethod(r#trait, method) => {
+                write!(f, "The trait `{}` has no method named `{}`", r#trait, method)
+            },
+
+            ErrorType::DuplicateMethod(method) => {
+                write!(f, "Method `{}` was declared more than once", method)
+            },
+
+            ErrorType::MissingSelfParameter(method) => {
+                write!(f, "The first parameter of the method `{}` must be `self`", method)
+            },
+
+            ErrorType::NoMethod(r#type, method) => {
+                write!(f, "`{}` has no method named `{}`", r#type, method)
+            },
+
+            ErrorType::AmbiguousMethod(r#type, method) => {
+                write!(
+                    f,
+                    "`{}` has more than one method named `{}`, call it as a function instead",
+                    r#type, method
+                )
+            },
+
+            ErrorType::CannotDeclareMethods(r#type) => {
+                write!(f, "Methods can only be declared for structs, not `{}`", r#type)
+            },
+
+            ErrorType::NotAssignable => {
+                write!(f, "Only variables, fields and list elements can be modified")
+            },
+
+            ErrorType::MissingArgument(parameter) => {
+                write!(f, "No argument was given for the parameter `{}`", parameter)
+            },
+
+            ErrorType::DuplicateArgument(parameter) => {
+                write!(f, "More than one argument was given for the parameter `{}`", parameter)
+            },
+
+            ErrorType::UnknownArgument(parameter) => {
+                write!(f, "The function has no parameter named `{}`", parameter)
+            },
+
+            ErrorType::PositionalAfterNamed => {
+                write!(
+                    f,
+                    "Arguments passed by position must come before arguments passed by name"
+                )
+            },
+
+            ErrorType::NamedArgumentsNotAllowed => {
+                write!(
+                    f,
+                    "Arguments can only be passed by name when calling a function by its name"
+                )
+            },
+
+            ErrorType::DefaultNotAllowed => {
+                write!(
+                    f,
+                    "Only the parameters of functions can have default values, not those of methods"
+                )
+            },
+
+            ErrorType::DefaultNotConstant => {
+                write!(
+                    f,
+                    "The default value of a parameter must be a literal, a constant, or an operation on them"
+                )
+            },
+
+            ErrorType::VariadicNotLast => {
+                write!(f, "Only the last parameter of a function can be variadic")
+            },
+
+            ErrorType::VariadicNotList(r#type) => {
+                write!(
+                    f,
+                    "A variadic parameter collects its arguments into a list, so its type must be a `List`, not `{}`",
+                    r#type
+                )
+            },
+
+            ErrorType::VariadicNotAllowed => {
+                write!(
+                    f,
+                    "Only the parameters of functions can be variadic, not those of methods"
+                )
+            },
+
+            ErrorType::VariadicArgumentByName(parameter) => {
+                write!(
+                    f,
+                    "The variadic parameter `{}` cannot be passed by name, only by the arguments after the others",
+                    parameter
+                )
+            },
+
+            ErrorType::ImmutableVariable(name) => {
+                write!(f, "`{}` cannot be modified, as it was declared without `mut`", name)
+            },
+
+            ErrorType::CapturedVariable(name) => {
+                write!(
+                    f,
+                    "`{}` cannot be modified inside a function, as it was declared outside of it",
+                    name
+                )
+            },
+
+            ErrorType::CapturedMutableVariable(name) => {
+                write!(
+                    f,
+                    "`{}` cannot be used inside a function, as it was declared with `mut` outside of it",
+                    name
+                )
+            },
+
+            ErrorType::CyclicTypeAlias(name) => {
+                write!(f, "The type alias `{}` refers to itself", name)
+            },
+
+            ErrorType::RecursiveStruct(name) => {
+                write!(
+                    f,
+                    "Every `{}` would contain another `{}`, so none could ever be created",
+                    name, name
+                )
+            },
+
+            ErrorType::NestedTooDeeply => {
+                write!(f, "This code is nested too deeply to be checked")
+            },
+
+            ErrorType::DuplicateTypeParameter(name) => {
+                write!(f, "Type parameter `{}` was declared more than once", name)
+            },
+
+            ErrorType::UnusedVariable(name) => {
+                write!(f, "`{}` is never used", name)
+            },
+
+            ErrorType::UnreachableCode => {
+                write!(f, "Unreachable code")
+            },
+
+            ErrorType::ShadowedVariable(name) => {
+                write!(f, "`{}` shadows a variable with the same name", name)
+            },
+
+            ErrorType::UnknownLint(name) => {
+                write!(f, "Unknown lint: `{}`", name)
+            },
+        }
+    }
+}
+
+impl Error {
+    pub fn new(error_type: ErrorType, span: Span) -> Self {
+        Self {
+            error_type: Box::new(error_type),
+            span,
+            severity: Severity::Error,
+            details: None,
+        }
+    }
+
+    /// Creates a warning, which is reported as an error instead if its lint is denied.
+    pub fn warning(error_type: ErrorType, span: Span) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::new(error_type, span)
+        }
+    }
+
+    /// Returns the details of the error, creating them if the error has none yet.
+    fn details_mut(&mut self) -> &mut Details {
+        self.details.get_or_insert_with(Box::default)
+    }
+
+    /// Adds a [Label] pointing at another span related to the error.
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.details_mut().labels.push(Label {
+            span,
+            message: message.into(),
+        });
+
+        self
+    }
+
+    /// Adds a note explaining why the error occurred.
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.details_mut().notes.push(note.into());
+        self
+    }
+
+    /// Adds a suggestion for how to fix the error.
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.details_mut().help.push(help.into());
+        self
+    }
+
+    /// Adds the [trace] of calls being made when the error occurred, unless it already has the trace of a more deeply
+    /// nested call, which includes these calls.
+    pub fn with_trace(mut self, trace: &[Frame]) -> Self {
+        if !trace.is_empty() && self.details.as_ref().is_none_or(|it| it.trace.is_empty()) {
+            self.details_mut().trace = trace.to_vec();
+        }
+
+        self
+    }
+
+    /// Returns each function in the trace with the location in the [input] that it was running when the error occurred,
+    /// from the top level of the program to the innermost call, or nothing if the error has no trace.
+    fn stack(&self, input: &str) -> Vec<(&str, Location)> {
+        let Some(trace) = self.details.as_ref().map(|it| &it.trace).filter(|it| !it.is_empty()) else {
+            return vec![];
+        };
+
+        // Each function was running the next call when the error occurred, and the innermost was running the error.
+        let functions = std::iter::once("<main>").chain(trace.iter().map(|it| it.function.as_str()));
+        let spans = trace.iter().map(|it| it.span).chain(std::iter::once(self.span));
+
+        functions.zip(spans).map(|(function, span)| (function, span.start_location(input))).collect()
+    }
+
+    /// Returns the error as a single line of JSON, for tools to read.
+    /// The error was found in the [input] read from [file].
+    pub fn to_json(&self, file: &str, input: &str) -> String {
+        let empty = Details::default();
+        let details = self.details.as_deref().unwrap_or(&empty);
+
+        let labels = details.labels.iter().map(|label| {
+            json::object(&[
+                ("span", json::span(label.span, input)),
+                ("message", json::string(&label.message)),
+            ])
+        });
+
+        let stack = self.stack(input).into_iter().map(|(function, location)| {
+            json::object(&[
+                ("function", json::string(function)),
+                ("file", json::string(file)),
+                ("line", (location.line + 1).to_string()),
+            ])
+        });
+
+        json::object(&[
+            ("file", json::string(file)),
+            ("severity", json::string(self.severity.name())),
+            ("code", self.error_type.code().map_or("null".to_string(), json::string)),
+            ("message", json::string(&self.error_type.to_string())),
+            ("span", json::span(self.span, input)),
+            ("labels", json::array(labels)),
+            ("notes", json::array(details.notes.iter().map(|it| json::string(it)))),
+            ("help", json::array(details.help.iter().map(|it| json::string(it)))),
+            ("trace", json::array(stack)),
+        ])
+    }
+
+    /// Prints the error in the style of rustc: the message, followed by every line of the source code that the error
+    /// or one of its labels points at, then any notes and suggestions, and then the calls being made at runtime.
+    /// The error was found in the [input] read from [file].
+    pub fn print_error(&self, file: &str, input: String) {
+        let lines = input.lines().collect::<Vec<_>>();
+        let empty = Details::default();
+        let details = self.details.as_deref().unwrap_or(&empty);
+
+        // The primary span is underlined with `^`, and the labels with `-`.
+        let mut markers = Marker::of(self.span, None, true, &input, &lines);
+        for label in &details.labels {
+            markers.extend(Marker::of(label.span, Some(&label.message), false, &input, &lines));
+        }
+
+        markers.sort_by_key(|it| (it.line, it.start));
+
+        let last_line = markers.iter().map(|it| it.line).max().unwrap_or_default();
+        let width = (last_line + 1).to_string().len();
+        let gutter = format!("{} |", " ".repeat(width)).blue().bold();
+
+        let location = self.span.start_location(&input);
+        let header = match self.error_type.code() {
+            Some(code) => format!("{}[{}]", self.severity.name(), code),
+            None => self.severity.name().to_string(),
+        };
+
+        let header = match self.severity {
+            Severity::Error => header.red(),
+            Severity::Warning => header.yellow(),
+        };
+
+        eprintln!("{}: {}", header.bold(), self.error_type.to_string().bold());
+        eprintln!(
+            "{}{} line {}, column {}",
+            " ".repeat(width),
+            "-->".blue().bold(),
+            location.line + 1,
+            location.column + 1
+        );
+        eprintln!("{}", gutter);
+
+        let mut previous: Option<usize> = None;
+        for chunk in markers.chunk_by(|a, b| a.line == b.line) {
+            let line = chunk[0].line;
+
+            // Lines between two marked lines are shown if there is only one of them, and elided otherwise.
+            match previous {
+                Some(previous) if line == previous + 2 => {
+                    let text = lines.get(previous + 1).copied().unwrap_or_default();
+                    Self::print_line(&Window::of(text, &[]), text, previous + 1, width);
+                },
+                Some(previous) if line > previous + 2 => eprintln!("{}", "...".blue().bold()),
+                _ => {},
+            }
+
+            let text = lines.get(line).copied().unwrap_or_default();
+            let window = Window::of(text, chunk);
+
+            Self::print_line(&window, text, line, width);
+            Self::print_markers(&window.markers(chunk), &gutter);
+
+            previous = Some(line);
+        }
+
+        for note in &details.notes {
+            eprintln!("{} {} {}", " ".repeat(width), "= note:".bold(), note);
+        }
+
+        for help in &details.help {
+            eprintln!("{} {} {}", " ".repeat(width), "= help:".bold(), help);
+        }
+
+        Self::print_stack(&self.stack(&input), file);
+        eprintln!();
+    }
+
+    /// Prints the functions in a [stack], most recent call last, in the style of Python.
+    /// A function that recursed until it overflowed the stack would fill the screen, so repeated frames are counted instead.
+    fn print_stack(stack: &[(&str, Location)], file: &str) {
+        if stack.is_empty() {
+            return;
+        }
+
+        eprintln!("{}", "stack trace (most recent call last):".bold());
+        for chunk in stack.chunk_by(|a, b| a == b) {
+            let (function, location) = chunk[0];
+            eprintln!("    at {} ({}:{})", function, file, location.line + 1);
+
+            match chunk.len() {
+                1 => {},
+                2 => eprintln!("    ... and 1 more identical call"),
+                length => eprintln!("    ... and {} more identical calls", length - 1),
+            }
+        }
+    }
+
+    /// Prints the part of a single line of the source code, its [text], that is visible in the [window],
+    /// preceded by its line number.
+    fn print_line(window: &Window, text: &str, line: usize, width: usize) {
+        let number = format!("{:>width$} |", line + 1, width = width).blue().bold();
+        eprintln!("{} {}", number, window.text(text).white());
+    }
+
+    /// Prints the underlines for the [markers] on a single line, which must be sorted by their start.
+    /// The message of the rightmost marker is printed next to its underline,
+    /// and the message of each marker before it on a line of its own, connected to its underline by a `|`.
+    fn print_markers(markers: &[Marker], gutter: &ColoredString) {
+        let length = markers.iter().map(|it| it.end).max().unwrap_or_default();
+        let mut underline = vec![' '; length];
+
+        // The primary span is drawn last, so that it is not hidden by a label which overlaps it.
+        for marker in markers.iter().filter(|it| !it.primary).chain(markers.iter().filter(|it| it.primary)) {
+            let char = if marker.primary { '^' } else { '-' };
+            underline[marker.start..marker.end].fill(char);
+        }
+
+        let underline = underline.into_iter().collect::<String>();
+
+        let (last, rest) = markers.split_last().expect("a line is only printed if it has a marker");
+        match last.message {
+            Some(message) => eprintln!("{} {} {}", gutter, underline.bold(), message.bold()),
+            None => eprintln!("{} {}", gutter, underline.bold()),
+        }
+
+        for (index, marker) in rest.iter().enumerate().rev() {
+            let Some(message) = marker.message else {
+                continue;
+            };
+
+            let mut connectors = String::new();
+            for other in &rest[..index] {
+                connectors.push_str(&" ".repeat(other.start.saturating_sub(connectors.chars().count())));
+                connectors.push('|');
+            }
+
+            connectors.push_str(&" ".repeat(marker.start.saturating_sub(connectors.chars().count())));
+            eprintln!("{} {}", gutter, format!("{}|", connectors).bold());
+            eprintln!("{} {}{}", gutter, connectors.bold(), message.bold());
+        }
+    }
+}
+
+/// The most characters of a line of source code that are printed. Longer lines are cut off around their markers.
+const MAX_LINE_WIDTH: usize = 120;
+
+/// The number of characters before the first marker on a long line that are printed, to show what leads up to it.
+const LINE_CONTEXT: usize = 40;
+
+/// What is printed in place of the part of a long line that is cut off.
+const ELLIPSIS: &str = "...";
+
+/// The columns of a line of source code that are printed, from [start] up to [end].
+struct Window {
+    start: usize,
+    end: usize,
+
+    /// The number of characters in the whole line.
+    length: usize,
+}
+
+impl Window {
+    /// Returns the window that shows the [markers] on a line whose text is [text].
+    /// A line that is too long is cut off, starting a little before its primary marker,
+    /// and widened to show as many of the other markers closest to it as fit.
+    fn of(text: &str, markers: &[Marker]) -> Self {
+        let length = text.chars().count();
+        if length <= MAX_LINE_WIDTH {
+            return Self {
+                start: 0,
+                end: length,
+                length,
+            };
+        }
+
+        // The primary marker may be on another line, in which case every marker on this one is as important.
+        let (mut primary, mut secondary): (Vec<&Marker>, Vec<&Marker>) = markers.iter().partition(|it| it.primary);
+        if primary.is_empty() {
+            primary = std::mem::take(&mut secondary);
+        }
+
+        let mut first = primary.iter().map(|it| it.start).min().unwrap_or_default();
+        let mut last = primary.iter().map(|it| it.end).max().unwrap_or_default();
+
+        secondary.sort_by_key(|it| it.start.abs_diff(first).min(it.end.abs_diff(last)));
+        for marker in secondary {
+            if marker.end.max(last) - marker.start.min(first) <= MAX_LINE_WIDTH {
+                first = first.min(marker.start);
+                last = last.max(marker.end);
+            }
+        }
+
+        // The context before the markers is cut short if the end of the last one would otherwise be cut off.
+        let mut start = first.saturating_sub(LINE_CONTEXT);
+        if last - first <= MAX_LINE_WIDTH {
+            start = start.max(last.saturating_sub(MAX_LINE_WIDTH));
+        }
+
+        let start = start.min(length - MAX_LINE_WIDTH);
+
+        Self {
+            start,
+            end: start + MAX_LINE_WIDTH,
+            length,
+        }
+    }
+
+    /// Returns the visible part of [text], with an ellipsis on each side that is cut off.
+    /// Tabs and other control characters are printed as spaces, so that every character takes up one column.
+    fn text(&self, text: &str) -> String {
+        let visible = text.chars().skip(self.start).take(self.end - self.start);
+        let visible = visible.map(|it| if it.is_control() { ' ' } else { it }).collect::<String>();
+
+        let before = if self.start > 0 { ELLIPSIS } else { "" };
+        let after = if self.end < self.length { ELLIPSIS } else { "" };
+
+        format!("{}{}{}", before, visible, after)
+    }
+
+    /// Returns the [markers] moved to the columns that they are printed at,
+    /// where any part of them that is cut off is moved to the nearest visible column.
+    /// A secondary marker that is cut off entirely is left out, as it would otherwise point at the wrong code.
+    fn markers<'a>(&self, markers: &[Marker<'a>]) -> Vec<Marker<'a>> {
+        let offset = if self.start > 0 { ELLIPSIS.len() } else { 0 };
+
+        // A marker at the end of the line, e.g. at the end of the input, is still visible just after it.
+        let last = if self.end < self.length { self.end } else { self.end + 1 };
+        let column = |column: usize| column.clamp(self.start, last) - self.start + offset;
+
+        markers
+            .iter()
+            .filter(|marker| marker.primary || (marker.end > self.start && marker.start <= last))
+            .map(|marker| {
+                let start = column(marker.start);
+                Marker {
+                    start,
+                    end: column(marker.end).max(start + 1),
+                    ..*marker
+                }
+            })
+            .collect()
+    }
+}
+
+/// The part of a span on a single line, to underline when printing an error, with the message of its label.
+#[derive(Clone, Copy)]
+struct Marker<'a> {
+    line: usize,
+    start: usize,
+    end: usize,
+    message: Option<&'a str>,
+    primary: bool,
+}
+
+impl<'a> Marker<'a> {
+    /// Returns the markers that underline [span] in [source], whose lines are [lines].
+    /// A span across several lines is underlined from its start to the end of its first line,
+    /// and from the start of the code on its last line to its end, which is where its message is shown.
+    fn of(span: Span, message: Option<&'a str>, primary: bool, source: &str, lines: &[&str]) -> Vec<Self> {
+        let start = span.start_location(source);
+        let end = span.end_location(source);
+
+        // Every span is underlined by at least one character, even if it is empty, e.g. at the end of the input.
+        if start.line == end.line {
+            return vec![Marker {
+                line: start.line,
+                start: start.column,
+                end: end.column.max(start.column + 1),
+                message,
+                primary,
+            }];
+        }
+
+        let first_line = lines.get(start.line).copied().unwrap_or_default();
+        let last_line = lines.get(end.line).copied().unwrap_or_default();
+        let indentation = last_line.chars().take_while(|it| it.is_whitespace()).count();
+
+        vec![
+            Marker {
+                line: start.line,
+                start: start.column,
+                end: first_line.chars().count().max(start.column + 1),
+                message: None,
+                primary,
+            },
+            Marker {
+                line: end.line,
+                start: indentation.min(end.column),
+                end: end.column.max(indentation.min(end.column) + 1),
+                message,
+                primary,
+            },
+        ]
+    }
+}
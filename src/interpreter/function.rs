@@ -1,11 +1,15 @@
-use super::value::Value;
-use crate::r#type::Type;
+use super::{value::Value, Interpreter};
+use crate::{
+    r#type::{GenericParameter, Type},
+    Error,
+};
 
 pub trait Function {
     /// Call the function with the given arguments.
     /// The number of arguments will match the number of arguments returned by `arguments()`,
     /// and the types of the arguments will also match.
-    fn call(&self, arguments: Vec<Value>) -> Value;
+    /// The interpreter is passed so that the function can call trait methods on its arguments.
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Error>;
 
     /// The return type of the function.
     /// If the function returns nothing, the return type should be `Type::Void`.
@@ -14,6 +18,25 @@ pub trait Function {
     /// An empty vector means that the function takes no arguments.
     /// If the function takes a variable number of arguments, the last type should be `Type::Void`.
     fn arguments(&self) -> Vec<Type>;
+
+    /// The names of the arguments, in the same order as their types, which they can also be passed by.
+    fn argument_names(&self) -> Vec<&'static str>;
+
+    /// The function's type parameters, which can be used in its arguments and return type.
+    fn type_parameters(&self) -> Vec<GenericParameter> {
+        vec![]
+    }
+
+    /// The type of the function, which is generic if the function has any type parameters.
+    fn r#type(&self) -> Type {
+        let r#type = Type::Function(self.arguments(), Box::new(self.return_type()));
+
+        if self.type_parameters().is_empty() {
+            r#type
+        } else {
+            Type::Generic(self.type_parameters(), Box::new(r#type))
+        }
+    }
 }
 
 /// A collection of built-in functions.
@@ -22,27 +45,36 @@ pub trait Function {
 pub struct BuiltinFunctions;
 
 impl BuiltinFunctions {
+    /// The names of every built-in function.
+    pub const NAMES: [&'static str; 2] = ["print", "display"];
+
     /// Get a built-in function by name.
     /// If the function does not exist, returns `None`.
-    pub fn get(&self, name: &str) -> Option<&dyn Function> {
+    pub fn get(&self, name: &str) -> Option<&'static dyn Function> {
         match name {
             "print" => Some(&PrintFunction),
+            "display" => Some(&DisplayFunction),
             _ => None,
         }
     }
 }
 
+/// The type parameter shared by the built-in functions, which can only be called with printable values.
+fn printable(parameter: &str) -> GenericParameter {
+    GenericParameter::new(parameter.to_string(), vec!["Printable".to_string()])
+}
+
 pub struct PrintFunction;
 
 /// The built-in `print` function.
-/// This function takes a value of any type, and returns nothing.
+/// This function takes any number of values of printable types, and returns nothing.
 impl Function for PrintFunction {
-    fn call(&self, arguments: Vec<Value>) -> Value {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Error> {
         for argument in arguments {
-            println!("{}", argument);
+            println!("{}", interpreter.display(argument)?);
         }
 
-        Value::Void
+        Ok(Value::Void)
     }
 
     fn return_type(&self) -> Type {
@@ -50,6 +82,41 @@ impl Function for PrintFunction {
     }
 
     fn arguments(&self) -> Vec<Type> {
-        vec![Type::Any]
+        vec![Type::Parameter("T".to_string()), Type::Void]
+    }
+
+    fn argument_names(&self) -> Vec<&'static str> {
+        vec!["value"]
+    }
+
+    fn type_parameters(&self) -> Vec<GenericParameter> {
+        vec![printable("T")]
+    }
+}
+
+pub struct DisplayFunction;
+
+/// The built-in `display` function, which is the method of the built-in `Printable` trait.
+/// This function takes a value of any printable type, and returns how it would be printed.
+impl Function for DisplayFunction {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Error> {
+        let value = arguments.into_iter().next().expect("the type checker should have ensured there is one argument");
+        Ok(Value::String(interpreter.display(value)?))
+    }
+
+    fn return_type(&self) -> Type {
+        Type::String
+    }
+
+    fn arguments(&self) -> Vec<Type> {
+        vec![Type::Parameter("Self".to_string())]
+    }
+
+    fn argument_names(&self) -> Vec<&'static str> {
+        vec!["value"]
+    }
+
+    fn type_parameters(&self) -> Vec<GenericParameter> {
+        vec![printable("Self")]
     }
 }
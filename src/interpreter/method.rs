@@ -0,0 +1,163 @@
+use super::{value::Value, Interpreter};
+use crate::{
+    r#type::{GenericParameter, Type},
+    Error,
+};
+
+pub trait BuiltinMethod {
+    /// Call the method on the receiver with the given arguments.
+    /// The types of the receiver and the arguments will match the type of the method.
+    /// The receiver can only be modified if the method is mutating.
+    fn call(&self, interpreter: &mut Interpreter, receiver: &mut Value, arguments: Vec<Value>) -> Result<Value, Error>;
+
+    /// The type of the method, as a function whose first parameter is the receiver.
+    fn r#type(&self) -> Type;
+
+    /// Whether the method modifies its receiver, in which case the receiver must be a variable, a field or a list element.
+    fn is_mutating(&self) -> bool {
+        false
+    }
+}
+
+/// A table of the methods of the built-in types.
+/// This is used by the resolver to check method calls, and by the interpreter to call them.
+#[derive(Default)]
+pub struct BuiltinMethods;
+
+impl BuiltinMethods {
+    /// Get a method of a built-in type by the name of the type and the name of the method.
+    /// If the type has no such method, returns `None`.
+    pub fn get(&self, r#type: &str, name: &str) -> Option<&'static dyn BuiltinMethod> {
+        match (r#type, name) {
+            ("String", "length") => Some(&StringLengthMethod),
+            ("String", "upper") => Some(&UpperMethod),
+            ("String", "lower") => Some(&LowerMethod),
+            ("List", "length") => Some(&ListLengthMethod),
+            ("List", "push") => Some(&PushMethod),
+
+            // The built-in types implement the built-in `Printable` trait with a built-in method.
+            ("Integer" | "String" | "Boolean" | "List" | "Tuple", "display") => Some(&DisplayMethod),
+
+            _ => None,
+        }
+    }
+}
+
+/// Returns the type of a method which is generic over the element type `T` of a list.
+fn list_method(mut parameters: Vec<Type>, return_type: Type) -> Type {
+    let element_type = Type::Parameter("T".to_string());
+    parameters.insert(0, Type::List(Box::new(element_type)));
+
+    Type::Generic(
+        vec![GenericParameter::new("T".to_string(), vec![])],
+        Box::new(Type::Function(parameters, Box::new(return_type))),
+    )
+}
+
+/// `String.length()`, which returns the number of characters in the string.
+pub struct StringLengthMethod;
+
+impl BuiltinMethod for StringLengthMethod {
+    fn call(&self, _: &mut Interpreter, receiver: &mut Value, _: Vec<Value>) -> Result<Value, Error> {
+        let Value::String(string) = receiver else {
+            unreachable!("the type checker should have ensured that the receiver is a string");
+        };
+
+        Ok(Value::Integer(string.chars().count() as i32))
+    }
+
+    fn r#type(&self) -> Type {
+        Type::Function(vec![Type::String], Box::new(Type::Integer))
+    }
+}
+
+/// `String.upper()`, which returns the string in upper case.
+pub struct UpperMethod;
+
+impl BuiltinMethod for UpperMethod {
+    fn call(&self, _: &mut Interpreter, receiver: &mut Value, _: Vec<Value>) -> Result<Value, Error> {
+        let Value::String(string) = receiver else {
+            unreachable!("the type checker should have ensured that the receiver is a string");
+        };
+
+        Ok(Value::String(string.to_uppercase()))
+    }
+
+    fn r#type(&self) -> Type {
+        Type::Function(vec![Type::String], Box::new(Type::String))
+    }
+}
+
+/// `String.lower()`, which returns the string in lower case.
+pub struct LowerMethod;
+
+impl BuiltinMethod for LowerMethod {
+    fn call(&self, _: &mut Interpreter, receiver: &mut Value, _: Vec<Value>) -> Result<Value, Error> {
+        let Value::String(string) = receiver else {
+            unreachable!("the type checker should have ensured that the receiver is a string");
+        };
+
+        Ok(Value::String(string.to_lowercase()))
+    }
+
+    fn r#type(&self) -> Type {
+        Type::Function(vec![Type::String], Box::new(Type::String))
+    }
+}
+
+/// `List.length()`, which returns the number of elements in the list.
+pub struct ListLengthMethod;
+
+impl BuiltinMethod for ListLengthMethod {
+    fn call(&self, _: &mut Interpreter, receiver: &mut Value, _: Vec<Value>) -> Result<Value, Error> {
+        let Value::List(elements) = receiver else {
+            unreachable!("the type checker should have ensured that the receiver is a list");
+        };
+
+        Ok(Value::Integer(elements.len() as i32))
+    }
+
+    fn r#type(&self) -> Type {
+        list_method(vec![], Type::Integer)
+    }
+}
+
+/// `List.push(element)`, which adds an element to the end of the list.
+pub struct PushMethod;
+
+impl BuiltinMethod for PushMethod {
+    fn call(&self, _: &mut Interpreter, receiver: &mut Value, arguments: Vec<Value>) -> Result<Value, Error> {
+        let Value::List(elements) = receiver else {
+            unreachable!("the type checker should have ensured that the receiver is a list");
+        };
+
+        elements.extend(arguments);
+        Ok(Value::Void)
+    }
+
+    fn r#type(&self) -> Type {
+        list_method(vec![Type::Parameter("T".to_string())], Type::Void)
+    }
+
+    fn is_mutating(&self) -> bool {
+        true
+    }
+}
+
+/// `display()` on a built-in type, which returns how the value would be printed.
+pub struct DisplayMethod;
+
+impl BuiltinMethod for DisplayMethod {
+    fn call(&self, interpreter: &mut Interpreter, receiver: &mut Value, _: Vec<Value>) -> Result<Value, Error> {
+        Ok(Value::String(interpreter.display(receiver.clone())?))
+    }
+
+    fn r#type(&self) -> Type {
+        let receiver = Type::Parameter("Self".to_string());
+
+        Type::Generic(
+            vec![GenericParameter::new("Self".to_string(), vec!["Printable".to_string()])],
+            Box::new(Type::Function(vec![receiver], Box::new(Type::String))),
+        )
+    }
+}
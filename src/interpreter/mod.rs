@@ -1,53 +1,587 @@
 pub mod function;
+pub mod method;
 pub mod value;
 
-use std::collections::HashMap;
+use std::{collections::HashMap, rc::Rc};
 
 use crate::{
-    ast::{Expression, Identifier, Statement},
+    ast::{self, Expression, Identifier, Pattern, Statement},
     resolver::{ExpressionVisitor, StatementVisitor},
-    Error, ErrorType,
+    span::Span,
+    stack, Error, ErrorType, Frame,
 };
 use function::*;
+use method::*;
 use value::*;
 
-#[derive(Default)]
 pub struct Interpreter {
-    variables: HashMap<Identifier, Value>,
+    /// The scopes that are currently visible, from outermost to innermost.
+    /// While a closure is being called, these are replaced by its environment and its own local scope.
+    scopes: Vec<HashMap<Identifier, Value>>,
     builtin_functions: BuiltinFunctions,
+    builtin_methods: BuiltinMethods,
+
+    /// The methods that implement each trait, by the name of the trait and the name of the type they are implemented for.
+    implementations: HashMap<(String, String), HashMap<String, Rc<Closure>>>,
+
+    /// The methods that structs declare themselves, by the name of the struct and the name of the method.
+    methods: HashMap<(String, String), Rc<Closure>>,
+
+    /// The functions hoisted by the block currently running and the blocks around it,
+    /// which the functions declared in those blocks can call before their declarations have run.
+    hoisted: Option<Rc<Hoisted>>,
+
+    /// The function and method calls currently being made, excluding tail calls, from outermost to innermost.
+    /// These are the trace of any error that occurs while they are being made.
+    frames: Vec<Frame>,
+
+    /// The greatest number of calls that can be nested inside each other before a stack overflow error is raised.
+    recursion_limit: usize,
+}
+
+/// The default recursion limit, which is low enough that the interpreter does not run out of stack space itself.
+pub const DEFAULT_RECURSION_LIMIT: usize = 1000;
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self {
+            scopes: vec![HashMap::new()],
+            builtin_functions: BuiltinFunctions,
+            builtin_methods: BuiltinMethods,
+            implementations: HashMap::new(),
+            methods: HashMap::new(),
+            hoisted: None,
+            frames: vec![],
+            recursion_limit: DEFAULT_RECURSION_LIMIT,
+        }
+    }
 }
 
 impl Interpreter {
-    pub fn print_variables(&self) {
-        println!("Variables:");
-        for (identifier, value) in &self.variables {
-            println!("  - {}: {:?}", identifier.name, value);
+    /// Creates an interpreter which allows at most [recursion_limit] calls to be nested inside each other.
+    pub fn with_recursion_limit(recursion_limit: usize) -> Self {
+        Self {
+            recursion_limit,
+            ..Self::default()
+        }
+    }
+
+    /// Logs the value of every variable in scope, e.g. once the program has finished running.
+    pub fn log_variables(&self) {
+        for (identifier, value) in self.scopes.iter().flatten() {
+            log::debug!("`{}` = {:?}", identifier.name, value);
+        }
+    }
+
+    /// Looks up the value of a variable, starting from the innermost scope,
+    /// and then any function that was hoisted by the blocks around the code running.
+    fn variable(&self, identifier: &Identifier) -> Option<Value> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(identifier))
+            .cloned()
+            .or_else(|| self.hoisted.as_ref()?.get(identifier))
+    }
+
+    /// Declares a variable in the innermost scope.
+    fn declare_variable(&mut self, identifier: Identifier, value: Value) -> Result<(), Error> {
+        let scope = self.scopes.last_mut().expect("there should always be at least one scope");
+
+        // If there is already a variable with the same name, throw an error.
+        if scope.contains_key(&identifier) {
+            return Err(Error::new(
+                ErrorType::VariableAlreadyDeclared(identifier.name),
+                identifier.span,
+            ));
+        }
+
+        scope.insert(identifier, value);
+        Ok(())
+    }
+
+    /// Calls a closure with the given arguments, returning the value of its first executed return statement.
+    /// A tail call made by the closure replaces it, so that the host stack does not grow with each tail call.
+    fn call_closure(&mut self, mut closure: Rc<Closure>, mut arguments: Vec<Value>) -> Result<Value, Error> {
+        loop {
+            let mut locals = HashMap::new();
+
+            // A named function can always refer to itself, which allows it to be recursive.
+            if let Some(identifier) = &closure.function.identifier {
+                locals.insert(identifier.clone(), Value::Function(closure.clone()));
+            }
+
+            for (parameter, argument) in closure.function.parameters.iter().zip(arguments) {
+                locals.extend(Self::destructure(&parameter.pattern, argument));
+            }
+
+            let outer_scopes = std::mem::replace(&mut self.scopes, vec![(*closure.environment).clone(), locals]);
+            let outer_hoisted = std::mem::replace(&mut self.hoisted, closure.hoisted.clone());
+            let result = self.execute_body(closure.function.body.clone());
+            self.scopes = outer_scopes;
+            self.hoisted = outer_hoisted;
+
+            let error = match result {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+
+            match *error.error_type {
+                ErrorType::TailCall(next, next_arguments) => {
+                    // The call replaces the function that made it, so it takes its place in the trace.
+                    if let (Some(frame), Some(identifier)) = (self.frames.last_mut(), &next.function.identifier) {
+                        frame.function = identifier.name.clone();
+                    }
+
+                    (closure, arguments) = (next, next_arguments);
+                },
+
+                error_type => {
+                    return Err(Error {
+                        error_type: Box::new(error_type),
+                        ..error
+                    })
+                },
+            }
+        }
+    }
+
+    /// Makes a call to [function] at [span], raising a stack overflow error if too many calls are already nested inside
+    /// each other. An error raised by the call is given the trace of the calls being made when it occurred.
+    fn nested_call(
+        &mut self,
+        function: String,
+        span: Span,
+        call: impl FnOnce(&mut Self) -> Result<Value, Error>,
+    ) -> Result<Value, Error> {
+        if self.frames.len() >= self.recursion_limit {
+            return Err(self.stack_overflow(Some(self.recursion_limit), span));
+        }
+
+        if stack::is_exhausted() {
+            return Err(self.stack_overflow(None, span));
+        }
+
+        self.frames.push(Frame { function, span });
+        let result = call(self).map_err(|error| match *error.error_type {
+            ErrorType::Return(_) | ErrorType::TailCall(..) => error,
+            _ => error.with_trace(&self.frames),
+        });
+        self.frames.pop();
+
+        result
+    }
+
+    /// Returns the error for a call or expression at [span] that would overflow the stack,
+    /// either by reaching the recursion [limit] or by running out of stack space before it.
+    fn stack_overflow(&self, limit: Option<usize>, span: Span) -> Error {
+        let help = match limit {
+            Some(_) => "return the result of the recursive call directly so that it is a tail call, or raise the limit with `--recursion-limit`",
+            None => "split up deeply nested expressions, or raise `--recursion-limit`, which also makes the stack larger",
+        };
+
+        Error::new(ErrorType::StackOverflow(limit), span).with_help(help).with_trace(&self.frames)
+    }
+
+    /// Matches a value against a pattern, returning the value of each variable that it binds.
+    fn destructure(pattern: &Pattern, value: Value) -> Vec<(Identifier, Value)> {
+        match (pattern, value) {
+            (Pattern::Identifier(identifier), value) => vec![(identifier.clone(), value)],
+            (Pattern::Wildcard(_), _) => vec![],
+
+            (Pattern::Tuple(patterns, _), Value::Tuple(elements)) => patterns
+                .iter()
+                .zip(elements)
+                .flat_map(|(pattern, element)| Self::destructure(pattern, element))
+                .collect(),
+
+            _ => unreachable!("the type checker should have ensured that only tuples are destructured"),
+        }
+    }
+
+    /// Executes a block of statements in a new scope, so that the variables declared in it are not visible outside of it.
+    fn execute_block(&mut self, statements: Vec<Statement>) -> Result<(), Error> {
+        self.scopes.push(HashMap::new());
+        let result = self.with_hoisted(declares_functions(&statements), |interpreter| {
+            statements.into_iter().try_for_each(|statement| interpreter.visit_statement(statement))
+        });
+        self.scopes.pop();
+
+        result
+    }
+
+    /// Executes the body of a function, or the statements of the program.
+    fn execute_body(&mut self, body: Vec<Statement>) -> Result<Value, Error> {
+        self.with_hoisted(declares_functions(&body), |interpreter| {
+            for statement in body {
+                let Err(error) = interpreter.visit_statement(statement) else {
+                    continue;
+                };
+
+                return match *error.error_type {
+                    ErrorType::Return(value) => Ok(value.unwrap_or(Value::Void)),
+                    error_type => Err(Error {
+                        error_type: Box::new(error_type),
+                        ..error
+                    }),
+                };
+            }
+
+            Ok(Value::Void)
+        })
+    }
+
+    /// Runs [execute] with a new group of hoisted functions for a block, if the block [declares_functions].
+    fn with_hoisted<T>(&mut self, declares_functions: bool, execute: impl FnOnce(&mut Self) -> T) -> T {
+        if !declares_functions {
+            return execute(self);
+        }
+
+        let hoisted = Rc::new(Hoisted::new(self.hoisted.clone()));
+        let outer_hoisted = self.hoisted.replace(hoisted);
+        let result = execute(self);
+        self.hoisted = outer_hoisted;
+
+        result
+    }
+
+    /// Returns a closure of the [function], which captures a snapshot of every variable that is currently in scope.
+    fn closure(&self, function: ast::Function) -> Rc<Closure> {
+        let mut environment = HashMap::new();
+        for scope in &self.scopes {
+            environment.extend(scope.clone());
+        }
+
+        Rc::new(Closure {
+            function: Rc::new(function),
+            environment: Rc::new(environment),
+            hoisted: self.hoisted.clone(),
+        })
+    }
+
+    /// Calls the method of a trait, using the implementation for the type of the first argument.
+    /// The built-in types implement the built-in traits with built-in methods instead.
+    fn call_method(&mut self, r#trait: &str, name: &str, mut arguments: Vec<Value>) -> Result<Value, Error> {
+        let head = arguments
+            .first()
+            .and_then(|it| it.r#type().head())
+            .expect("the type checker should have ensured that the trait is implemented");
+
+        let closure = self
+            .implementations
+            .get(&(r#trait.to_string(), head.clone()))
+            .and_then(|methods| methods.get(name))
+            .cloned();
+
+        if let Some(closure) = closure {
+            return self.call_closure(closure, arguments);
+        }
+
+        let method = self
+            .builtin_methods
+            .get(&head, name)
+            .expect("the type checker should have ensured that the trait is implemented");
+
+        let mut receiver = arguments.remove(0);
+        method.call(self, &mut receiver, arguments)
+    }
+
+    /// Calls a method which is not a trait method on the [receiver], which is the value of [place].
+    /// This is either one of the struct's own methods or a method of a built-in type,
+    /// in which case the receiver is stored back into the place if the method modifies it.
+    fn call_own_method(
+        &mut self,
+        place: Option<Place>,
+        mut receiver: Value,
+        name: &str,
+        mut arguments: Vec<Value>,
+    ) -> Result<Value, Error> {
+        let head = receiver.r#type().head().expect("the type checker should have ensured that the method exists");
+
+        if let Some(closure) = self.methods.get(&(head.clone(), name.to_string())).cloned() {
+            arguments.insert(0, receiver);
+            return self.call_closure(closure, arguments);
+        }
+
+        let method =
+            self.builtin_methods.get(&head, name).expect("the type checker should have ensured that the method exists");
+
+        let result = method.call(self, &mut receiver, arguments)?;
+        if method.is_mutating() {
+            let place = place.expect("the type checker should have ensured that only places are modified");
+            self.assign(place, receiver)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Evaluates the indices in a [place], which is a variable, or a field or element of a place,
+    /// so that it can be read and then stored into without evaluating them again.
+    fn visit_place(&mut self, place: Expression) -> Result<Place, Error> {
+        match place {
+            Expression::Identifier(_, variable) => Ok(Place {
+                variable,
+                path: Vec::new(),
+            }),
+
+            Expression::FieldAccess { value, field, .. } => {
+                let mut place = self.visit_place(*value)?;
+                place.path.push(Step::Field(field.name));
+                Ok(place)
+            },
+
+            Expression::Index { value, index, span, .. } => {
+                let mut place = self.visit_place(*value)?;
+                let Value::Integer(index) = self.visit_expression(*index)? else {
+                    unreachable!("the type checker should have ensured that lists are indexed by integers");
+                };
+
+                place.path.push(Step::Element(index, span));
+                Ok(place)
+            },
+
+            Expression::TupleAccess { value, index, .. } => {
+                let mut place = self.visit_place(*value)?;
+                place.path.push(Step::TupleElement(index));
+                Ok(place)
+            },
+
+            _ => unreachable!("the type checker should have ensured that only places are assigned to"),
+        }
+    }
+
+    /// Returns the value stored in a place.
+    fn read(&self, place: &Place) -> Result<Value, Error> {
+        let mut value =
+            self.variable(&place.variable).expect("the type checker should have ensured that the variable is declared");
+
+        for step in &place.path {
+            value = step.select(&mut value)?.clone();
+        }
+
+        Ok(value)
+    }
+
+    /// Stores a value into a place, replacing the field or element of the variable that the place refers to.
+    fn assign(&mut self, place: Place, value: Value) -> Result<(), Error> {
+        let mut target = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(&place.variable))
+            .expect("the type checker should have ensured that the variable is declared");
+
+        for step in &place.path {
+            target = step.select(target)?;
+        }
+
+        *target = value;
+        Ok(())
+    }
+
+    /// Returns how a value is printed.
+    /// The built-in types implement `Printable` themselves, while structs use their implementation of it.
+    /// A value of type `Any` is only known to be printable here, so any other value is reported.
+    pub fn display(&mut self, value: Value) -> Result<String, Error> {
+        // Values are displayed by a call from a built-in function, which is made where that function was called.
+        let span = self.frames.last().map(|it| it.span).unwrap_or_default();
+
+        match value {
+            Value::List(elements) => Ok(format!("[{}]", self.display_elements(elements)?)),
+            Value::Tuple(elements) => Ok(format!("({})", self.display_elements(elements)?)),
+
+            Value::Struct(ref name, _)
+                if !self.implementations.contains_key(&("Printable".to_string(), name.clone())) =>
+            {
+                Err(Error::new(
+                    ErrorType::TraitNotImplemented("Printable".to_string(), value.r#type()),
+                    span,
+                ))
+            },
+
+            Value::Function(_) | Value::Method(_) | Value::Void => Err(Error::new(
+                ErrorType::TraitNotImplemented("Printable".to_string(), value.r#type()),
+                span,
+            )),
+
+            Value::Struct(..) => {
+                let display = |interpreter: &mut Self| interpreter.call_method("Printable", "display", vec![value]);
+
+                match self.nested_call("display".to_string(), span, display)? {
+                    Value::String(string) => Ok(string),
+                    _ => unreachable!("the type checker should have ensured that `display` returns a string"),
+                }
+            },
+
+            value => Ok(value.to_string()),
+        }
+    }
+
+    /// Returns how the elements of a list or tuple are printed, separated by commas.
+    fn display_elements(&mut self, elements: Vec<Value>) -> Result<String, Error> {
+        let mut strings = Vec::new();
+        for element in elements {
+            strings.push(self.display(element)?);
+        }
+
+        Ok(strings.join(", "))
+    }
+
+    /// Calls the [callee] with the given arguments.
+    /// A tail call of a closure is handed back to the closure making it as an error, which then makes the call itself.
+    /// Evaluates the [callee] of a call, which is done before the call is made so that it is not part of its trace.
+    fn callee(&mut self, callee: Expression) -> Result<Callee, Error> {
+        // Built-in functions are called by name, unless a variable with the same name is in scope.
+        if let Expression::Identifier(_, identifier) = &callee {
+            if self.variable(identifier).is_none() {
+                // Look up the function in the functions map.
+                let function = self.builtin_functions.get(&identifier.name).ok_or(Error::new(
+                    ErrorType::UnknownFunction(identifier.name.clone()),
+                    identifier.span,
+                ))?;
+
+                return Ok(Callee::Builtin(function));
+            }
+        }
+
+        self.visit_expression(callee).map(Callee::Value)
+    }
+
+    fn call(&mut self, callee: Callee, arguments: Vec<Value>, span: Span, tail_call: bool) -> Result<Value, Error> {
+        let value = match callee {
+            // At this point, the typechecker should have ensured that the types of the arguments match the types of the parameters.
+            Callee::Builtin(function) => return function.call(self, arguments),
+            Callee::Value(value) => value,
+        };
+
+        match value {
+            Value::Function(closure) if tail_call => Err(Error::new(ErrorType::TailCall(closure, arguments), span)),
+            Value::Function(closure) => self.call_closure(closure, arguments),
+            Value::Method(method) => self.call_method(&method.r#trait, &method.name, arguments),
+            value => Err(Error::new(ErrorType::NotCallable(value.r#type()), span)),
         }
     }
 }
 
 impl StatementVisitor<()> for Interpreter {
+    /// Executes the statements of a program, whose functions are hoisted like those of any other block.
+    fn visit_statements(&mut self, statements: Vec<Statement>) -> Result<Vec<()>, Error> {
+        let value = self.execute_body(statements)?;
+        log::debug!("returning: {:?}", value);
+
+        Ok(vec![])
+    }
+
     fn visit_statement(&mut self, statement: Statement) -> Result<(), Error> {
         match statement {
-            Statement::Let { identifier, value, .. } => {
-                // If there is already a variable with the same name, throw an error.
-                if self.variables.contains_key(&identifier) {
-                    return Err(Error::new(
-                        ErrorType::VariableAlreadyDeclared(identifier.name),
-                        identifier.position,
-                    ));
-                }
+            Statement::Assignment { place, value, .. } => {
+                let value = self.visit_expression(value)?;
+                let place = self.visit_place(place)?;
+                self.assign(place, value)
+            },
 
-                // Evaluate the value of the expression.
+            Statement::Let { pattern, value, .. } => {
+                // Evaluate the value of the expression, and bind it to the variables of the pattern.
                 let value = self.visit_expression(value)?;
-                self.variables.insert(identifier, value);
+
+                for (identifier, value) in Self::destructure(&pattern, value) {
+                    self.declare_variable(identifier, value)?;
+                }
 
                 Ok(())
             },
 
-            Statement::Return { value, position } => {
+            Statement::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                let Value::Boolean(condition) = self.visit_expression(condition)? else {
+                    unreachable!("the type checker should have ensured that the condition is a boolean");
+                };
+
+                match (condition, else_branch) {
+                    (true, _) => self.execute_block(then_branch),
+                    (false, Some(else_branch)) => self.execute_block(else_branch),
+                    (false, None) => Ok(()),
+                }
+            },
+
+            Statement::Return { value, span } => {
                 let value = value.map(|it| self.visit_expression(it)).transpose()?;
-                Err(Error::new(ErrorType::Return(value), position))
+                Err(Error::new(ErrorType::Return(value), span))
+            },
+
+            Statement::Function(function) => {
+                let identifier = function.identifier.clone().expect("function statements should always be named");
+                let mut closure = self.closure(function);
+
+                // The functions declared before this one in the same block are found through the hoisted functions,
+                // rather than the closure's own environment, as they in turn refer to this function through them.
+                if let (Some(hoisted), Some(closure)) = (&self.hoisted, Rc::get_mut(&mut closure)) {
+                    Rc::make_mut(&mut closure.environment).retain(|identifier, _| !hoisted.contains(identifier));
+                    hoisted.declare(identifier.clone(), closure);
+                }
+
+                self.declare_variable(identifier, Value::Function(closure))
+            },
+
+            // Structs only exist at compile time.
+            // The resolver has already replaced the value of a constant with a literal.
+            Statement::Constant { identifier, value, .. } => {
+                let value = self.visit_expression(value)?;
+                self.declare_variable(identifier, value)
+            },
+
+            Statement::Struct(_) | Statement::TypeAlias { .. } => Ok(()),
+
+            Statement::Newtype(newtype) => {
+                let value = self.visit_expression(Expression::Function(newtype.constructor()))?;
+                self.declare_variable(newtype.identifier, value)
+            },
+
+            Statement::Trait(r#trait) => {
+                for method in r#trait.methods {
+                    let value = Value::Method(Rc::new(Method {
+                        r#trait: r#trait.identifier.name.clone(),
+                        name: method.identifier.name.clone(),
+                    }));
+                    self.declare_variable(method.identifier, value)?;
+                }
+
+                Ok(())
+            },
+
+            Statement::Implementation(implementation) => {
+                let head = implementation
+                    .r#type
+                    .head()
+                    .expect("the type checker should have ensured that the type can implement traits");
+
+                let mut methods = HashMap::new();
+                for mut method in implementation.methods {
+                    // Within a method, its name does not refer to the method itself.
+                    let identifier = method.identifier.take().expect("methods should always be named");
+
+                    let Value::Function(closure) = self.visit_expression(Expression::Function(method))? else {
+                        unreachable!("a function expression should always evaluate to a function");
+                    };
+
+                    methods.insert(identifier.name, closure);
+                }
+
+                match implementation.r#trait {
+                    Some(r#trait) => {
+                        self.implementations.insert((r#trait.name, head), methods);
+                    },
+
+                    None => {
+                        self.methods.extend(methods.into_iter().map(|(name, closure)| ((head.clone(), name), closure)))
+                    },
+                }
+
+                Ok(())
             },
 
             Statement::Expression(expression) => {
@@ -60,13 +594,35 @@ impl StatementVisitor<()> for Interpreter {
 
 impl ExpressionVisitor<Value> for Interpreter {
     fn visit_expression(&mut self, expression: Expression) -> Result<Value, Error> {
+        // Expressions nested inside each other take up stack space as well as calls,
+        // so the stack can run out before the recursion limit is reached.
+        if stack::is_exhausted() {
+            return Err(self.stack_overflow(None, expression.span()));
+        }
+
         let value = match expression {
-            Expression::IntegerLiteral(value) => Value::Integer(value),
-            Expression::StringLiteral(value) => Value::String(value),
+            Expression::IntegerLiteral(value, _) => Value::Integer(value),
+            Expression::StringLiteral(value, _) => Value::String(value),
+            Expression::BooleanLiteral(value, _) => Value::Boolean(value),
+
+            Expression::Is { value, target, .. } => {
+                let value = self.visit_expression(*value)?;
+                Value::Boolean(value.is_instance(&target))
+            },
+
+            Expression::Cast { value, target, span } => {
+                let value = self.visit_expression(*value)?;
+
+                if !value.is_instance(&target) {
+                    return Err(Error::new(ErrorType::InvalidCast(value.r#type(), target), span));
+                }
+
+                value
+            },
             Expression::BinaryOperation {
                 left,
                 operator,
-                position,
+                span,
                 right,
                 ..
             } => {
@@ -74,44 +630,190 @@ impl ExpressionVisitor<Value> for Interpreter {
                 let left = self.visit_expression(*left)?;
                 let right = self.visit_expression(*right)?;
 
-                // If the binary operation fails, the types are incompatible.
-                left.binary_operation(operator, right.clone()).ok_or(Error::new(
-                    ErrorType::InvalidBinaryOperation(left, operator, right),
-                    position,
-                ))?
+                left.binary_operation(operator, right, span)?
             },
 
             Expression::Identifier(.., identifier) => {
-                // Look up the variable in the variables map.
-                self.variables
-                    .get(&identifier)
-                    .ok_or(Error::new(
-                        ErrorType::UnableToResolveType(identifier.clone().name),
-                        identifier.position,
-                    ))?
-                    .clone()
+                // Look up the variable in the visible scopes.
+                self.variable(&identifier).ok_or(Error::new(
+                    ErrorType::UnableToResolveType(identifier.clone().name),
+                    identifier.span,
+                ))?
             },
 
             Expression::FunctionCall {
-                identifier, arguments, ..
+                callee,
+                arguments,
+                span,
+                tail_call,
+                ..
             } => {
                 // Evaluate the values to be passed to the function.
                 let mut values = Vec::new();
-                for argument in &arguments {
-                    values.push(self.visit_expression(argument.clone())?);
+                for argument in arguments {
+                    values.push(self.visit_expression(argument.value)?);
                 }
 
-                // Look up the function in the functions map.
-                let function = self.builtin_functions.get(&identifier.name).ok_or(Error::new(
-                    ErrorType::UnknownFunction(identifier.name.clone()),
-                    identifier.position,
-                ))?;
+                let function = match &*callee {
+                    Expression::Identifier(_, identifier) => identifier.name.clone(),
+                    _ => "<closure>".to_string(),
+                };
+
+                let callee = self.callee(*callee)?;
+                self.nested_call(function, span, |interpreter| {
+                    interpreter.call(callee, values, span, tail_call)
+                })?
+            },
+
+            Expression::Function(function) => Value::Function(self.closure(function)),
+
+            Expression::List { elements, .. } => {
+                let mut values = Vec::new();
+                for element in elements {
+                    values.push(self.visit_expression(element)?);
+                }
+
+                Value::List(values)
+            },
+
+            Expression::Tuple { elements, .. } => {
+                let mut values = Vec::new();
+                for element in elements {
+                    values.push(self.visit_expression(element)?);
+                }
+
+                Value::Tuple(values)
+            },
+
+            Expression::TupleAccess { value, index, .. } => {
+                let Value::Tuple(mut elements) = self.visit_expression(*value)? else {
+                    unreachable!("the type checker should have ensured that only tuples have elements");
+                };
+
+                elements.swap_remove(index)
+            },
+
+            Expression::Index { value, index, span, .. } => {
+                // At this point, the typechecker should have ensured that this is a list indexed by an integer.
+                let (Value::List(elements), Value::Integer(index)) =
+                    (self.visit_expression(*value)?, self.visit_expression(*index)?)
+                else {
+                    unreachable!("the type checker should have ensured that only lists are indexed");
+                };
+
+                usize::try_from(index)
+                    .ok()
+                    .and_then(|it| elements.get(it))
+                    .cloned()
+                    .ok_or(Error::new(ErrorType::IndexOutOfBounds(index, elements.len()), span))?
+            },
+
+            Expression::StructLiteral { identifier, fields, .. } => {
+                let mut values = Vec::new();
+                for (field, value) in fields {
+                    values.push((field.name, self.visit_expression(value)?));
+                }
 
-                // At this point, the typechecker should have ensured that the types of the arguments match the types of the parameters.
-                function.call(values)
+                Value::Struct(identifier.name, values)
+            },
+
+            Expression::MethodCall {
+                value,
+                method,
+                arguments,
+                r#trait,
+                span,
+                ..
+            } => {
+                // A method which modifies its receiver stores it back into the same place that it was read from.
+                let (place, receiver) = if value.is_place() {
+                    let place = self.visit_place(*value)?;
+                    let receiver = self.read(&place)?;
+                    (Some(place), receiver)
+                } else {
+                    (None, self.visit_expression(*value)?)
+                };
+
+                let mut values = Vec::new();
+                for argument in arguments {
+                    values.push(self.visit_expression(argument)?);
+                }
+
+                self.nested_call(method.name.clone(), span, |interpreter| match r#trait {
+                    Some(r#trait) => {
+                        values.insert(0, receiver);
+                        interpreter.call_method(&r#trait, &method.name, values)
+                    },
+
+                    None => interpreter.call_own_method(place, receiver, &method.name, values),
+                })?
+            },
+
+            Expression::FieldAccess { value, field, .. } => {
+                let Value::Struct(_, fields) = self.visit_expression(*value)? else {
+                    unreachable!("the type checker should have ensured that only structs have fields");
+                };
+
+                fields
+                    .into_iter()
+                    .find(|(name, _)| *name == field.name)
+                    .map(|(_, value)| value)
+                    .expect("the type checker should have ensured that the field exists")
             },
         };
 
         Ok(value)
     }
 }
+
+/// A place that a value can be stored in, with any indices in it already evaluated.
+struct Place {
+    variable: Identifier,
+
+    /// The fields and elements leading from the variable to the place, outermost first.
+    path: Vec<Step>,
+}
+
+/// A field or element of a value, which leads to a place within it.
+enum Step {
+    Field(String),
+
+    /// An element of a list, along with the span of the indexing expression, where it is reported if it is out of bounds.
+    Element(i32, Span),
+    TupleElement(usize),
+}
+
+impl Step {
+    /// Returns the field or element of the [value] that this step leads to.
+    fn select<'a>(&self, value: &'a mut Value) -> Result<&'a mut Value, Error> {
+        match (self, value) {
+            (Step::Field(name), Value::Struct(_, fields)) => Ok(fields
+                .iter_mut()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value)
+                .expect("the type checker should have ensured that the field exists")),
+
+            (Step::Element(index, span), Value::List(elements)) => {
+                let length = elements.len();
+                usize::try_from(*index)
+                    .ok()
+                    .and_then(|it| elements.get_mut(it))
+                    .ok_or(Error::new(ErrorType::IndexOutOfBounds(*index, length), *span))
+            },
+
+            (Step::TupleElement(index), Value::Tuple(elements)) => Ok(&mut elements[*index]),
+            _ => unreachable!("the type checker should have ensured that the place exists"),
+        }
+    }
+}
+
+/// What a function call calls, once it has been evaluated.
+enum Callee {
+    Builtin(&'static dyn Function),
+    Value(Value),
+}
+
+/// Whether any of the [statements] declare a named function, which would be hoisted when they run.
+fn declares_functions(statements: &[Statement]) -> bool {
+    statements.iter().any(|statement| matches!(statement, Statement::Function(_)))
+}
@@ -1,39 +1,235 @@
-use std::fmt::Display;
+use std::{cell::RefCell, collections::HashMap, fmt::Display, rc::Rc};
 
-use crate::ast::Operator;
+use crate::{
+    ast::{Function, Identifier, Operator},
+    r#type::Type,
+    span::Span,
+    Error, ErrorType,
+};
 
 #[derive(Debug, Clone)]
 pub enum Value {
     Integer(i32),
     String(String),
+    Boolean(bool),
+    Function(Rc<Closure>),
+
+    Method(Rc<Method>),
+    List(Vec<Value>),
+    Tuple(Vec<Value>),
+
+    /// An instance of a struct, with its fields in the order that they were declared.
+    Struct(String, Vec<(String, Value)>),
     Void,
 }
 
+/// A function value, along with the variables that were in scope where it was declared.
+pub struct Closure {
+    pub function: Rc<Function>,
+    pub environment: Rc<HashMap<Identifier, Value>>,
+
+    /// The functions hoisted by the blocks that the closure was declared in, which it can call even if they were
+    /// declared after it.
+    pub hoisted: Option<Rc<Hoisted>>,
+}
+
+/// The functions declared by a running block, which are added as their declarations run.
+/// Through these, functions can call the functions declared after them in the same block, including each other.
+#[derive(Default)]
+pub struct Hoisted {
+    /// The closure of each function declared so far, without the hoisted functions that it can call.
+    /// These are added when it is used, as the closure would otherwise refer back to itself and never be freed.
+    functions: RefCell<HashMap<Identifier, Closure>>,
+
+    /// The functions hoisted by the blocks around this one.
+    parent: Option<Rc<Hoisted>>,
+}
+
+impl Hoisted {
+    pub fn new(parent: Option<Rc<Hoisted>>) -> Self {
+        Self {
+            functions: RefCell::default(),
+            parent,
+        }
+    }
+
+    /// Adds a function declared by the block.
+    pub fn declare(&self, identifier: Identifier, closure: &Closure) {
+        let closure = Closure {
+            function: closure.function.clone(),
+            environment: closure.environment.clone(),
+            hoisted: None,
+        };
+
+        self.functions.borrow_mut().insert(identifier, closure);
+    }
+
+    /// Whether the block has declared a function called [identifier].
+    pub fn contains(&self, identifier: &Identifier) -> bool {
+        self.functions.borrow().contains_key(identifier)
+    }
+
+    /// Returns the closure of the function called [identifier] that was declared by this block or one around it.
+    pub fn get(self: &Rc<Self>, identifier: &Identifier) -> Option<Value> {
+        let mut hoisted = Some(self);
+
+        while let Some(current) = hoisted {
+            if let Some(closure) = current.functions.borrow().get(identifier) {
+                return Some(Value::Function(Rc::new(Closure {
+                    function: closure.function.clone(),
+                    environment: closure.environment.clone(),
+                    hoisted: Some(current.clone()),
+                })));
+            }
+
+            hoisted = current.parent.as_ref();
+        }
+
+        None
+    }
+}
+
+/// A method of a trait, which calls the implementation for the type of its first argument.
+#[derive(Debug)]
+pub struct Method {
+    /// The name of the trait that declares the method.
+    pub r#trait: String,
+
+    /// The name of the method.
+    pub name: String,
+}
+
+/// The body and environment of a closure are omitted, as they would make values unreadable.
+impl std::fmt::Debug for Closure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Closure({})", self.function.name())
+    }
+}
+
+/// Writes a comma separated list of values.
+fn write_values(f: &mut std::fmt::Formatter<'_>, values: &[Value]) -> std::fmt::Result {
+    for (index, value) in values.iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+
+        write!(f, "{}", value)?;
+    }
+
+    Ok(())
+}
+
 impl Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Value::Integer(value) => write!(f, "{}", value),
             Value::String(value) => write!(f, "{}", value),
+            Value::Boolean(value) => write!(f, "{}", value),
+            Value::Function(closure) => write!(f, "<fn {}>", closure.function.name()),
+            Value::Method(method) => write!(f, "<method {}.{}>", method.r#trait, method.name),
+
+            Value::List(elements) => {
+                write!(f, "[")?;
+                write_values(f, elements)?;
+                write!(f, "]")
+            },
+
+            Value::Tuple(elements) => {
+                write!(f, "(")?;
+                write_values(f, elements)?;
+                write!(f, ")")
+            },
+
+            Value::Struct(name, fields) => {
+                write!(f, "{} {{", name)?;
+                for (index, (field, value)) in fields.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+
+                    write!(f, " {}: {}", field, value)?;
+                }
+                write!(f, " }}")
+            },
+
             Value::Void => write!(f, "void"),
         }
     }
 }
 
 impl Value {
-    // This should only return None if the types are incompatible.
-    pub fn binary_operation(&self, operator: Operator, right: Value) -> Option<Value> {
-        match operator {
-            Operator::Add => self.add(right),
-            Operator::Subtract => self.sub(right),
-            Operator::Divide => self.divide(right),
-            Operator::Multiply => self.multiply(right),
+    /// Returns the type of the value.
+    pub fn r#type(&self) -> Type {
+        match self {
+            Value::Integer(_) => Type::Integer,
+            Value::String(_) => Type::String,
+            Value::Boolean(_) => Type::Boolean,
+            Value::Function(closure) => closure.function.r#type(),
+
+            // The type of a method depends on the implementation that it is called with.
+            Value::Method(..) => Type::default(),
+
+            // The element type of a list, or the type arguments of a struct, are not known at runtime.
+            Value::List(elements) => Type::List(Box::new(elements.first().map(Value::r#type).unwrap_or_default())),
+            Value::Struct(name, _) => Type::Struct(name.clone(), vec![]),
+            Value::Tuple(elements) => Type::Tuple(elements.iter().map(Value::r#type).collect()),
+
+            Value::Void => Type::Void,
+        }
+    }
+
+    /// Whether the value is of the given type, which the resolver has ensured can be checked at runtime.
+    pub fn is_instance(&self, r#type: &Type) -> bool {
+        match (self, r#type) {
+            (_, Type::Any) => true,
+            (_, Type::Union(members)) => members.iter().any(|it| self.is_instance(it)),
+
+            (Value::Integer(_), Type::Integer)
+            | (Value::String(_), Type::String)
+            | (Value::Boolean(_), Type::Boolean) => true,
+
+            (Value::List(elements), Type::List(element_type)) => elements.iter().all(|it| it.is_instance(element_type)),
+
+            (Value::Tuple(elements), Type::Tuple(types)) => {
+                elements.len() == types.len()
+                    && elements.iter().zip(types).all(|(value, r#type)| value.is_instance(r#type))
+            },
+
+            (Value::Struct(name, _), Type::Struct(struct_name, _)) => name == struct_name,
+            (Value::Void, Type::Void) => true,
+
+            _ => false,
+        }
+    }
+
+    /// Applies a binary operator to this value and the [right] value, in an operation at [span].
+    /// Integer arithmetic that overflows or divides by zero is an error, rather than wrapping around or panicking.
+    pub fn binary_operation(&self, operator: Operator, right: Value, span: Span) -> Result<Value, Error> {
+        if let (Operator::Divide, Value::Integer(0)) = (operator, &right) {
+            return Err(Error::new(ErrorType::DivisionByZero, span));
         }
+
+        let result = match operator {
+            Operator::Add => self.add(right.clone()),
+            Operator::Subtract => self.sub(right.clone()),
+            Operator::Divide => self.divide(right.clone()),
+            Operator::Multiply => self.multiply(right.clone()),
+        };
+
+        result.ok_or_else(|| {
+            let error_type = match (self, &right) {
+                (Value::Integer(_), Value::Integer(_)) => ErrorType::Overflow(self.clone(), operator, right),
+                _ => ErrorType::InvalidBinaryOperation(self.clone(), operator, right),
+            };
+
+            Error::new(error_type, span)
+        })
     }
 
-    // This should only return None if the types are incompatible.
+    // This should only return None if the types are incompatible, or the result overflows.
     pub fn add(&self, right: Value) -> Option<Value> {
         let value = match (self, right) {
-            (Value::Integer(left), Value::Integer(right)) => Value::Integer(left + right),
+            (Value::Integer(left), Value::Integer(right)) => Value::Integer(left.checked_add(right)?),
             (Value::String(left), Value::String(right)) => Value::String(format!("{}{}", left, right)),
 
             _ => return None,
@@ -42,11 +238,10 @@ impl Value {
         Some(value)
     }
 
-    // This should only return None if the types are incompatible.
+    // This should only return None if the types are incompatible, or the result overflows.
     pub fn sub(&self, right: Value) -> Option<Value> {
         let value = match (self, right) {
-            (Value::Integer(left), Value::Integer(right)) => Value::Integer(left + right),
-            (Value::String(left), Value::String(right)) => Value::String(format!("{}{}", left, right)),
+            (Value::Integer(left), Value::Integer(right)) => Value::Integer(left.checked_sub(right)?),
 
             _ => return None,
         };
@@ -54,11 +249,10 @@ impl Value {
         Some(value)
     }
 
-    // This should only return None if the types are incompatible.
+    // This should only return None if the types are incompatible, or the right-hand side is zero.
     pub fn divide(&self, right: Value) -> Option<Value> {
         let value = match (self, right) {
-            (Value::Integer(left), Value::Integer(right)) => Value::Integer(left + right),
-            (Value::String(left), Value::String(right)) => Value::String(format!("{}{}", left, right)),
+            (Value::Integer(left), Value::Integer(right)) => Value::Integer(left.checked_div(right)?),
 
             _ => return None,
         };
@@ -66,11 +260,10 @@ impl Value {
         Some(value)
     }
 
-    // This should only return None if the types are incompatible.
+    // This should only return None if the types are incompatible, or the result overflows.
     pub fn multiply(&self, right: Value) -> Option<Value> {
         let value = match (self, right) {
-            (Value::Integer(left), Value::Integer(right)) => Value::Integer(left + right),
-            (Value::String(left), Value::String(right)) => Value::String(format!("{}{}", left, right)),
+            (Value::Integer(left), Value::Integer(right)) => Value::Integer(left.checked_mul(right)?),
 
             _ => return None,
         };
@@ -1,25 +1,55 @@
 pub mod token;
 pub use token::*;
 
-use crate::{position::Position, Error, ErrorType, Stream};
+use crate::{error::lint::Lint, span::Span, Error, ErrorType, Stream};
 
 pub struct Lexer {
     stream: Stream<char>,
-    line: usize,
+
+    /// The byte offset of each character in the input, followed by the length of the input,
+    /// as the stream is indexed by characters but spans are byte offsets.
+    offsets: Vec<usize>,
+
+    /// The index of the first character of the token being lexed.
+    start: usize,
+
+    /// The currently open delimiters, innermost last.
+    /// Newlines are not significant directly inside of parentheses or brackets, so expressions can be split across lines there,
+    /// but they are significant again inside of a block, even if that block is within parentheses (e.g. a lambda).
+    delimiters: Vec<TokenType>,
+
+    /// The type of the last token that was emitted, used to decide whether a newline terminates a statement.
+    last_token_type: Option<TokenType>,
+
+    /// The errors found so far.
+    /// After an error, the rest of the line is skipped, and lexing continues from the next statement.
+    errors: Vec<Error>,
+
+    /// The lints allowed by `// cecelia:allow(...)` comments, which apply to the whole file.
+    allowed_lints: Vec<Lint>,
 }
 
 impl Lexer {
     pub fn new(input: String) -> Self {
+        let offsets = input.char_indices().map(|(offset, _)| offset).chain([input.len()]).collect();
+
         Lexer {
             stream: input.chars().collect::<Vec<char>>().into(),
-            line: 0,
+            offsets,
+            start: 0,
+            delimiters: vec![],
+            last_token_type: None,
+            errors: vec![],
+            allowed_lints: vec![],
         }
     }
 
-    pub fn parse(&mut self) -> Result<Vec<Token>, Error> {
+    /// Splits the input into tokens, returning every error that was found if it could not be.
+    pub fn parse(&mut self) -> Result<Vec<Token>, Vec<Error>> {
         let mut tokens = vec![];
 
         loop {
+            self.start = self.stream.index();
             let Some(char) = self.stream.consume() else {
                 break;
             };
@@ -30,23 +60,43 @@ impl Lexer {
                 '-' => self.token(TokenType::Minus),
                 '*' => self.token(TokenType::Asterisk),
                 ':' => self.token(TokenType::Colon),
-                '(' => self.token(TokenType::OpenParenthesis),
-                ')' => self.token(TokenType::CloseParenthesis),
+                ';' => self.token(TokenType::Semicolon),
                 ',' => self.token(TokenType::Comma),
 
-                '\n' => {
-                    self.line += 1;
-                    self.stream.visual_index = 0;
+                '|' => self.token(TokenType::Pipe),
+                '.' => self.parse_dots(),
+                '<' => self.token(TokenType::LessThan),
+                '>' => self.token(TokenType::GreaterThan),
+                '(' => self.open_delimiter(TokenType::OpenParenthesis),
+                ')' => self.close_delimiter(TokenType::CloseParenthesis),
+                '{' => self.open_delimiter(TokenType::OpenBrace),
+                '}' => self.close_delimiter(TokenType::CloseBrace),
+                '[' => self.open_delimiter(TokenType::OpenBracket),
+                ']' => self.close_delimiter(TokenType::CloseBracket),
 
-                    continue;
+                '\n' => {
+                    // A newline is only significant if it could terminate the statement before it.
+                    if self.is_newline_significant() {
+                        self.token(TokenType::Newline)
+                    } else {
+                        continue;
+                    }
                 },
 
-                '"' => self.parse_string()?,
+                '"' => match self.parse_string() {
+                    Ok(token) => token,
+                    Err(error) => {
+                        self.recover(error);
+                        continue;
+                    },
+                },
 
                 '/' => {
-                    // Ignore comments...
+                    // Ignore comments, other than to allow any lints that they name...
+                    // The newline itself is left in the stream, as it may still terminate a statement.
                     if let Some('/') = self.stream.peek() {
-                        self.skip_until('\n');
+                        let comment = self.skip_until('\n');
+                        self.parse_comment(&comment);
                         continue;
                     } else {
                         // ... but still emit a slash token for single `/` characters
@@ -54,35 +104,72 @@ impl Lexer {
                     }
                 },
 
-                ' ' => continue,
+                ' ' | '\t' | '\r' => continue,
 
                 _ => {
-                    if char.is_alphabetic() {
-                        self.parse_identifier(char)
+                    let token = if char.is_alphabetic() || char == '_' {
+                        Ok(self.parse_identifier(char))
                     } else if char.is_numeric() {
-                        self.parse_number(char)?
+                        self.parse_number(char)
                     } else {
-                        return Err(self.error(ErrorType::UnexpectedCharacter(char)));
+                        Err(self.error(ErrorType::UnexpectedCharacter(char)))
+                    };
+
+                    match token {
+                        Ok(token) => token,
+                        Err(error) => {
+                            self.recover(error);
+                            continue;
+                        },
                     }
                 },
             };
 
+            self.last_token_type = Some(token.token_type.clone());
             tokens.push(token);
         }
 
-        Ok(tokens)
+        if self.errors.is_empty() {
+            Ok(tokens)
+        } else {
+            Err(std::mem::take(&mut self.errors))
+        }
+    }
+
+    /// Returns the lints allowed by `// cecelia:allow(...)` comments in the input.
+    pub fn allowed_lints(&self) -> &[Lint] {
+        &self.allowed_lints
+    }
+
+    /// Records an [error], and skips the rest of the line so that lexing continues at the next statement.
+    fn recover(&mut self, error: Error) {
+        self.errors.push(error);
+        self.skip_until('\n');
+    }
+
+    /// Allows the lints named by a comment of the form `// cecelia:allow(unused, shadowing)`.
+    /// The [comment] is everything after its first slash.
+    fn parse_comment(&mut self, comment: &str) {
+        let Some(names) =
+            comment.trim_start_matches('/').trim().strip_prefix("cecelia:allow(").and_then(|it| it.strip_suffix(')'))
+        else {
+            return;
+        };
+
+        for name in names.split(',').map(str::trim) {
+            match Lint::from_name(name) {
+                Some(lint) => self.allowed_lints.push(lint),
+                None => self.errors.push(self.error(ErrorType::UnknownLint(name.to_string()))),
+            }
+        }
     }
 
     fn parse_identifier(&mut self, first_char: char) -> Token {
         let mut identifier = String::new();
         identifier.push(first_char);
 
-        loop {
-            let Some(char) = self.stream.peek() else {
-                break;
-            };
-
-            if char.is_alphabetic() {
+        while let Some(char) = self.stream.peek() {
+            if char.is_alphanumeric() || char == '_' {
                 self.stream.consume();
                 identifier.push(char);
             } else {
@@ -91,10 +178,9 @@ impl Lexer {
         }
 
         // Check if identifier is a keyword
-        let token_type = match identifier.as_str() {
-            "let" => TokenType::Keyword(Keyword::Let),
-            "return" => TokenType::Keyword(Keyword::Return),
-            _ => TokenType::Identifier(identifier),
+        let token_type = match Keyword::ALL.into_iter().find(|it| it.name() == identifier) {
+            Some(keyword) => TokenType::Keyword(keyword),
+            None => TokenType::Identifier(identifier),
         };
 
         self.token(token_type)
@@ -108,7 +194,9 @@ impl Lexer {
                 return Err(self.error(ErrorType::ExpectedCharacter('"')));
             };
 
+            // The newline is not part of the unterminated string.
             if char == '\n' {
+                self.stream.unconsume();
                 return Err(self.error(ErrorType::ExpectedCharacter('"')));
             }
 
@@ -126,11 +214,7 @@ impl Lexer {
         let mut number_string = String::new();
         number_string.push(char);
 
-        loop {
-            let Some(char) = self.stream.peek() else {
-                break;
-            };
-
+        while let Some(char) = self.stream.peek() {
             if char.is_numeric() {
                 self.stream.consume();
                 number_string.push(char);
@@ -145,23 +229,69 @@ impl Lexer {
             .map_err(|_| self.error(ErrorType::InvalidNumber(number_string)))
     }
 
-    fn skip_until(&mut self, until: char) {
-        while let Some(char) = self.stream.consume() {
+    /// Skips characters until the next occurrence of `until`, without consuming it, and returns the skipped characters.
+    fn skip_until(&mut self, until: char) -> String {
+        let mut skipped = String::new();
+
+        while let Some(char) = self.stream.peek() {
             if char == until {
                 break;
             }
+
+            self.stream.consume();
+            skipped.push(char);
         }
+
+        skipped
+    }
+
+    /// Whether a newline at the current position should be emitted as a [TokenType::Newline].
+    /// Like Go and Kotlin, a newline terminates a statement if the line ends in a token that can end a statement,
+    /// and we are not directly inside of any parentheses or brackets.
+    fn is_newline_significant(&self) -> bool {
+        if let Some(TokenType::OpenParenthesis | TokenType::OpenBracket) = self.delimiters.last() {
+            return false;
+        }
+
+        self.last_token_type.as_ref().is_some_and(TokenType::can_end_statement)
+    }
+
+    /// Parses a single dot, or three dots which mark a variadic parameter.
+    fn parse_dots(&mut self) -> Token {
+        if let Some('.') = self.stream.peek() {
+            self.stream.consume();
+
+            if let Some('.') = self.stream.peek() {
+                self.stream.consume();
+                return self.token(TokenType::Ellipsis);
+            }
+
+            self.stream.unconsume();
+        }
+
+        self.token(TokenType::Dot)
+    }
+
+    fn open_delimiter(&mut self, token_type: TokenType) -> Token {
+        self.delimiters.push(token_type.clone());
+        self.token(token_type)
+    }
+
+    fn close_delimiter(&mut self, token_type: TokenType) -> Token {
+        self.delimiters.pop();
+        self.token(token_type)
     }
 
-    fn position(&self) -> Position {
-        Position::new(self.stream.visual_index, self.line)
+    /// Returns the span from the start of the current token to the last character consumed.
+    fn span(&self) -> Span {
+        Span::new(self.offsets[self.start], self.offsets[self.stream.index()])
     }
 
     fn token(&self, token_type: TokenType) -> Token {
-        Token::new(token_type, self.position())
+        Token::new(token_type, self.span())
     }
 
     fn error(&self, error_type: ErrorType) -> Error {
-        Error::new(error_type, self.position())
+        Error::new(error_type, self.span())
     }
 }
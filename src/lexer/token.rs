@@ -1,4 +1,4 @@
-use crate::position::Position;
+use crate::span::Span;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TokenType {
@@ -11,6 +11,20 @@ pub enum TokenType {
     OpenParenthesis,  // (
     CloseParenthesis, // )
     Comma,            // ,
+    Semicolon,        // ;
+    Pipe,             // |
+    OpenBrace,        // {
+    CloseBrace,       // }
+    OpenBracket,      // [
+    CloseBracket,     // ]
+    LessThan,         // <
+    GreaterThan,      // >
+    Dot,              // .
+    Ellipsis,         // ...
+
+    /// A newline that terminates a statement.
+    /// Newlines that cannot terminate a statement (e.g. after an operator) are not emitted by the lexer.
+    Newline,
 
     Keyword(Keyword),
     Identifier(String),
@@ -18,20 +32,107 @@ pub enum TokenType {
     IntegerLiteral(i32),
 }
 
+impl TokenType {
+    /// Whether a statement can end with this token.
+    /// A newline following one of these tokens is treated as a statement terminator.
+    /// This includes the closing angle bracket of a type's arguments, e.g. in `type Ids = List<Integer>`.
+    pub fn can_end_statement(&self) -> bool {
+        matches!(
+            self,
+            TokenType::Identifier(_)
+                | TokenType::StringLiteral(_)
+                | TokenType::IntegerLiteral(_)
+                | TokenType::CloseParenthesis
+                | TokenType::CloseBrace
+                | TokenType::CloseBracket
+                | TokenType::GreaterThan
+                | TokenType::Keyword(Keyword::Return)
+                | TokenType::Keyword(Keyword::True)
+                | TokenType::Keyword(Keyword::False)
+        )
+    }
+
+    /// Whether this token terminates a statement.
+    pub fn is_statement_terminator(&self) -> bool {
+        matches!(self, TokenType::Semicolon | TokenType::Newline)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Keyword {
     Let,
+    Mut,
     Return,
+    Fn,
+    Struct,
+    Trait,
+    Impl,
+    For,
+    If,
+    Else,
+    Is,
+    As,
+    True,
+    False,
+    Type,
+    Newtype,
+    Const,
+}
+
+impl Keyword {
+    /// Every keyword, in the order they are listed above.
+    pub const ALL: [Keyword; 17] = [
+        Keyword::Let,
+        Keyword::Mut,
+        Keyword::Return,
+        Keyword::Fn,
+        Keyword::Struct,
+        Keyword::Trait,
+        Keyword::Impl,
+        Keyword::For,
+        Keyword::If,
+        Keyword::Else,
+        Keyword::Is,
+        Keyword::As,
+        Keyword::True,
+        Keyword::False,
+        Keyword::Type,
+        Keyword::Newtype,
+        Keyword::Const,
+    ];
+
+    /// Returns the keyword as it is written in source code.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Keyword::Let => "let",
+            Keyword::Mut => "mut",
+            Keyword::Return => "return",
+            Keyword::Fn => "fn",
+            Keyword::Struct => "struct",
+            Keyword::Trait => "trait",
+            Keyword::Impl => "impl",
+            Keyword::For => "for",
+            Keyword::If => "if",
+            Keyword::Else => "else",
+            Keyword::Is => "is",
+            Keyword::As => "as",
+            Keyword::True => "true",
+            Keyword::False => "false",
+            Keyword::Type => "type",
+            Keyword::Newtype => "newtype",
+            Keyword::Const => "const",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Token {
     pub token_type: TokenType,
-    pub position: Position,
+    pub span: Span,
 }
 
 impl Token {
-    pub fn new(token_type: TokenType, position: Position) -> Self {
-        Self { token_type, position }
+    pub fn new(token_type: TokenType, span: Span) -> Self {
+        Self { token_type, span }
     }
 }
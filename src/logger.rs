@@ -0,0 +1,37 @@
+use colored::Colorize;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// Writes what the interpreter is doing internally to stderr, so that stdout is left for the program's own output.
+/// Messages are prefixed with their level and the module that logged them, e.g. `debug(resolver):`.
+struct Logger;
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let level = match record.level() {
+            Level::Error => "error".red(),
+            Level::Warn => "warn".yellow(),
+            Level::Info => "info".green(),
+            Level::Debug => "debug".blue(),
+            Level::Trace => "trace".magenta(),
+        };
+
+        let module = record.target().rsplit("::").next().unwrap_or_default();
+        eprintln!("{}: {}", format!("{}({})", level, module).bold(), record.args());
+    }
+
+    fn flush(&self) {}
+}
+
+/// Logs messages of the given [level] and above, which is off unless the interpreter is run with `-v`.
+pub fn init(level: LevelFilter) {
+    log::set_logger(&Logger).expect("the logger should only be set once");
+    log::set_max_level(level);
+}
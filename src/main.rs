@@ -2,8 +2,11 @@ pub mod ast;
 pub mod error;
 pub mod interpreter;
 pub mod lexer;
-pub mod position;
+pub mod logger;
+pub mod report;
 pub mod resolver;
+pub mod span;
+pub mod stack;
 pub mod stream;
 pub mod r#type;
 pub mod visitor;
@@ -11,12 +14,21 @@ pub mod visitor;
 pub use error::*;
 
 use ast::*;
-use clap::{Parser, Subcommand};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 use colored::Colorize;
-use interpreter::Interpreter;
+use error::lint::{Level, Lint, Lints};
+use interpreter::{Interpreter, DEFAULT_RECURSION_LIMIT};
 use lexer::*;
+use log::LevelFilter;
+use report::{MessageFormat, Reporter};
 use resolver::*;
-use std::{fs, process::exit};
+use std::{
+    env, fs,
+    io::{self, IsTerminal},
+    panic,
+    process::exit,
+    thread,
+};
 use stream::*;
 
 #[derive(Parser)]
@@ -28,6 +40,79 @@ struct Args {
 
     #[arg(global = true)]
     file: Option<String>,
+
+    /// The greatest number of function calls that can be nested inside each other while the program runs.
+    #[arg(long, global = true, default_value_t = DEFAULT_RECURSION_LIMIT, value_parser = parse_recursion_limit)]
+    recursion_limit: usize,
+
+    /// How errors are written to stderr.
+    #[arg(long, global = true, value_enum, default_value_t = MessageFormat::Human)]
+    message_format: MessageFormat,
+
+    /// Does not report the given lint, e.g. `-A unused`.
+    /// Lints are allowed first, then warned about, and then denied, so `-D` takes precedence over the others.
+    #[arg(short = 'A', long = "allow", global = true, value_name = "LINT", value_parser = parse_lint)]
+    allow: Vec<Lint>,
+
+    /// Reports the given lint as a warning, which is the default for every lint.
+    #[arg(short = 'W', long = "warn", global = true, value_name = "LINT", value_parser = parse_lint)]
+    warn: Vec<Lint>,
+
+    /// Reports the given lint as an error, which stops the program from running.
+    #[arg(short = 'D', long = "deny", global = true, value_name = "LINT", value_parser = parse_lint)]
+    deny: Vec<Lint>,
+
+    /// Logs what the interpreter is doing internally to stderr. Pass it twice to log every step.
+    #[arg(short, long, global = true, action = ArgAction::Count, conflicts_with = "quiet")]
+    verbose: u8,
+
+    /// Only reports errors, without any warnings or summary.
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// When to colour what is written to stderr.
+    /// By default, it is coloured if stderr is a terminal and the `NO_COLOR` environment variable is not set.
+    #[arg(long, global = true, value_enum, default_value_t = Color::Auto)]
+    color: Color,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Color {
+    Auto,
+    Always,
+    Never,
+}
+
+impl Color {
+    /// Whether output should be coloured.
+    fn enabled(self) -> bool {
+        match self {
+            Color::Auto => env::var_os("NO_COLOR").is_none_or(|it| it.is_empty()) && io::stderr().is_terminal(),
+            Color::Always => true,
+            Color::Never => false,
+        }
+    }
+}
+
+/// Parses the name of a lint given on the command line.
+fn parse_lint(name: &str) -> Result<Lint, String> {
+    Lint::from_name(name).ok_or_else(|| {
+        let names = Lint::ALL.map(|it| it.name());
+        format!("expected one of: {}", names.join(", "))
+    })
+}
+
+/// Parses the recursion limit given on the command line, which must be small enough for its stack size to be counted.
+fn parse_recursion_limit(value: &str) -> Result<usize, String> {
+    let recursion_limit = value.parse::<usize>().map_err(|error| error.to_string())?;
+
+    match stack_size(recursion_limit) {
+        Some(_) => Ok(recursion_limit),
+        None => Err(format!(
+            "expected at most {}",
+            (usize::MAX - BASE_STACK_SIZE) / STACK_SIZE_PER_CALL
+        )),
+    }
 }
 
 #[derive(Subcommand)]
@@ -35,11 +120,28 @@ enum Command {
     Lex,
     Parse,
     Check,
+
+    /// Explains the error with the given code, e.g. `C0013`, with an example of code that causes it and how to fix it.
+    Explain {
+        code: String,
+    },
 }
 
 fn main() -> Result<(), std::io::Error> {
     let args = Args::parse();
 
+    colored::control::set_override(args.color.enabled());
+    logger::init(match args.verbose {
+        0 => LevelFilter::Off,
+        1 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    });
+
+    if let Some(Command::Explain { code }) = &args.command {
+        explain(code);
+        return Ok(());
+    }
+
     let input = match &args.file {
         Some(value) => fs::read_to_string(value)?,
         None => {
@@ -48,22 +150,88 @@ fn main() -> Result<(), std::io::Error> {
         },
     };
 
+    // Each nested function call takes up space on the interpreter's own stack,
+    // so the program is run on a thread with enough stack space for the recursion limit to be reached first.
+    let recursion_limit = args.recursion_limit;
+    let stack_size =
+        stack_size(recursion_limit).expect("the recursion limit should have been checked when it was parsed");
+    let thread = thread::Builder::new().stack_size(stack_size).spawn(move || {
+        stack::set_size(stack_size);
+        run(args, input)
+    });
+
+    let thread = match thread {
+        Ok(thread) => thread,
+        Err(error) => {
+            eprintln!(
+                "{}: Unable to allocate the stack for a recursion limit of {}: {}",
+                "error".red(),
+                recursion_limit,
+                error
+            );
+            exit(-1);
+        },
+    };
+
+    if let Err(panic) = thread.join() {
+        panic::resume_unwind(panic);
+    }
+
+    Ok(())
+}
+
+/// The stack space needed by anything other than nested function calls.
+const BASE_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// An upper bound on the stack space used by each nested function call, which is largest in debug builds.
+const STACK_SIZE_PER_CALL: usize = 128 * 1024;
+
+/// The stack space needed to run a program with the given recursion limit, unless it is too large to be counted.
+fn stack_size(recursion_limit: usize) -> Option<usize> {
+    recursion_limit.checked_mul(STACK_SIZE_PER_CALL).and_then(|it| it.checked_add(BASE_STACK_SIZE))
+}
+
+fn run(args: Args, input: String) {
+    let mut lints = Lints::default();
+    for (lints_at_level, level) in [
+        (&args.allow, Level::Allow),
+        (&args.warn, Level::Warn),
+        (&args.deny, Level::Deny),
+    ] {
+        for lint in lints_at_level {
+            lints.set(*lint, level);
+        }
+    }
+
+    let file = args.file.clone().unwrap_or_default();
+    let mut reporter = Reporter::new(args.message_format, file, input.clone(), lints, args.quiet);
+
     let result = match args.command {
         Some(Command::Lex) => lex(input.clone()),
         Some(Command::Parse) => parse(input.clone()),
-        Some(Command::Check) => check(input.clone()),
-        None => execute(input.clone()),
+        Some(Command::Check) => check(input.clone(), &mut reporter),
+        Some(Command::Explain { .. }) => unreachable!("explanations should be printed before reading any input"),
+        None => execute(input.clone(), args.recursion_limit, &mut reporter),
     };
 
-    if let Err(error) = result {
-        error.print_error(input);
-        exit(-1);
+    if let Err(errors) = result {
+        reporter.report(errors);
     }
 
-    Ok(())
+    reporter.finish();
 }
 
-fn lex(input: String) -> Result<(), Error> {
+fn explain(code: &str) {
+    match error::explanation::explanation(&code.to_uppercase()) {
+        Some(explanation) => print!("{}", explanation),
+        None => {
+            eprintln!("{}: No error has the code `{}`", "error".red().bold(), code);
+            exit(-1);
+        },
+    }
+}
+
+fn lex(input: String) -> Result<(), Vec<Error>> {
     let mut lexer = Lexer::new(input.clone());
     let tokens = lexer.parse()?;
 
@@ -71,7 +239,7 @@ fn lex(input: String) -> Result<(), Error> {
     Ok(())
 }
 
-fn parse(input: String) -> Result<(), Error> {
+fn parse(input: String) -> Result<(), Vec<Error>> {
     let mut lexer = Lexer::new(input.clone());
     let tokens = lexer.parse()?;
 
@@ -82,35 +250,45 @@ fn parse(input: String) -> Result<(), Error> {
     Ok(())
 }
 
-fn check(input: String) -> Result<(), Error> {
+/// Parses the [input] and resolves its types, reporting any warnings.
+/// Fails if there are any errors, including warnings whose lint is denied.
+fn resolve(input: String, reporter: &mut Reporter) -> Result<Vec<Statement>, Vec<Error>> {
     let mut lexer = Lexer::new(input.clone());
     let tokens = lexer.parse()?;
+    reporter.allow(lexer.allowed_lints());
 
     let mut ast = AST::new(tokens);
-    let mut statements = ast.parse()?;
+    let statements = ast.parse()?;
 
     let mut resolver = TypeResolver::default();
-    statements = resolver.visit_statements(statements)?;
+    let statements = resolver.resolve(statements);
+    reporter.report(resolver.warnings());
 
-    println!("{}: No errors found!", "success".green());
-    println!("{:#?}", statements);
+    let statements = statements?;
+    if reporter.has_errors() {
+        return Err(vec![]);
+    }
 
-    Ok(())
+    Ok(statements)
 }
 
-fn execute(input: String) -> Result<(), Error> {
-    let mut lexer = Lexer::new(input.clone());
-    let tokens = lexer.parse()?;
+fn check(input: String, reporter: &mut Reporter) -> Result<(), Vec<Error>> {
+    let statements = resolve(input, reporter)?;
 
-    let mut ast = AST::new(tokens);
-    let mut statements = ast.parse()?;
+    log::debug!("resolved statements: {:#?}", statements);
+    if reporter.prints_summary() {
+        eprintln!("{}: No errors found!", "success".green());
+    }
 
-    let mut resolver = TypeResolver::default();
-    statements = resolver.visit_statements(statements)?;
+    Ok(())
+}
+
+fn execute(input: String, recursion_limit: usize, reporter: &mut Reporter) -> Result<(), Vec<Error>> {
+    let statements = resolve(input, reporter)?;
 
-    let mut interpreter = Interpreter::default();
-    interpreter.visit_statements(statements)?;
+    let mut interpreter = Interpreter::with_recursion_limit(recursion_limit);
+    interpreter.visit_statements(statements).map_err(|error| vec![error])?;
 
-    interpreter.print_variables();
+    interpreter.log_variables();
     Ok(())
 }
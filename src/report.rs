@@ -0,0 +1,160 @@
+use clap::ValueEnum;
+use colored::Colorize;
+use std::process::exit;
+
+use crate::{
+    error::lint::{Level, Lint, Lints},
+    Error, Severity,
+};
+
+/// How errors are written to stderr.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum MessageFormat {
+    /// Coloured text pointing at the source code, for people to read.
+    Human,
+
+    /// A JSON object on its own line for each error, for tools to read.
+    Json,
+}
+
+/// Reports errors and warnings as they are found, keeping count of them for a summary at the end.
+pub struct Reporter {
+    format: MessageFormat,
+
+    /// The name of the file that the input was read from.
+    file: String,
+    input: String,
+
+    lints: Lints,
+
+    /// Whether only errors are printed, without any warnings or summary.
+    quiet: bool,
+
+    errors: usize,
+    warnings: usize,
+
+    /// The codes of the errors and warnings that have been reported, each only once.
+    codes: Vec<&'static str>,
+}
+
+impl Reporter {
+    pub fn new(format: MessageFormat, file: String, input: String, lints: Lints, quiet: bool) -> Self {
+        Self {
+            format,
+            file,
+            input,
+            lints,
+            quiet,
+            errors: 0,
+            warnings: 0,
+            codes: vec![],
+        }
+    }
+
+    /// Allows the [lints], e.g. those allowed by comments in the file, regardless of their level on the command line.
+    pub fn allow(&mut self, lints: &[Lint]) {
+        for lint in lints {
+            self.lints.set(*lint, Level::Allow);
+        }
+    }
+
+    /// Reports each of the [errors], other than warnings whose lint is allowed.
+    /// A warning whose lint is denied is reported as an error instead.
+    pub fn report(&mut self, errors: Vec<Error>) {
+        for mut error in errors {
+            if let (Severity::Warning, Some(lint)) = (error.severity, error.error_type.lint()) {
+                match self.lints.level(lint) {
+                    Level::Allow => continue,
+                    Level::Warn => {},
+                    Level::Deny => error.severity = Severity::Error,
+                }
+            }
+
+            match error.severity {
+                Severity::Error => self.errors += 1,
+                Severity::Warning => self.warnings += 1,
+            }
+
+            if self.quiet && error.severity == Severity::Warning {
+                continue;
+            }
+
+            if let Some(code) = error.error_type.code() {
+                if !self.codes.contains(&code) {
+                    self.codes.push(code);
+                }
+            }
+
+            match self.format {
+                MessageFormat::Human => error.print_error(&self.file, self.input.clone()),
+                MessageFormat::Json => eprintln!("{}", error.to_json(&self.file, &self.input)),
+            }
+        }
+    }
+
+    /// Whether a summary is printed for people to read, which is not the case in quiet mode or for other tools.
+    pub fn prints_summary(&self) -> bool {
+        matches!(self.format, MessageFormat::Human) && !self.quiet
+    }
+
+    /// Whether any errors have been reported, including warnings that were denied.
+    pub fn has_errors(&self) -> bool {
+        self.errors > 0
+    }
+
+    /// Prints how many errors and warnings were reported, and exits with a failure if any of them were errors.
+    pub fn finish(mut self) {
+        if self.prints_summary() {
+            self.print_summary();
+        }
+
+        if self.has_errors() {
+            exit(-1);
+        }
+    }
+
+    fn print_summary(&mut self) {
+        let warnings = count(self.warnings, "warning");
+
+        if self.errors > 0 {
+            let errors = count(self.errors, "error");
+            match self.warnings {
+                0 => eprintln!("{}: Found {}", "error".red().bold(), errors),
+                _ => eprintln!("{}: Found {} and {}", "error".red().bold(), errors, warnings),
+            }
+        } else if self.warnings > 0 {
+            eprintln!("{}: Found {}", "warning".yellow().bold(), warnings);
+        }
+
+        // The explanations are for warnings if no errors were reported.
+        let (article, noun) = if self.errors > 0 {
+            ("an", "error")
+        } else {
+            ("a", "warning")
+        };
+
+        self.codes.sort();
+        match self.codes.as_slice() {
+            [] => {},
+            [code] => eprintln!(
+                "For more information about this {}, try `cecelia explain {}`.",
+                noun, code
+            ),
+            [first, ..] => {
+                eprintln!("Some {}s have detailed explanations: {}.", noun, self.codes.join(", "));
+                eprintln!(
+                    "For more information about {} {}, try `cecelia explain {}`.",
+                    article, noun, first
+                );
+            },
+        }
+    }
+}
+
+/// Returns a number of things, e.g. `1 error` or `2 errors`.
+fn count(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {}", noun),
+        _ => format!("{} {}s", count, noun),
+    }
+}
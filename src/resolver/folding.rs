@@ -0,0 +1,43 @@
+use crate::{
+    ast::{Expression, Operator},
+    interpreter::value::Value,
+    span::Span,
+    Error,
+};
+
+/// Evaluates a binary operation whose operands are both literals, returning the result as a literal.
+/// If either operand is not a literal, the operation can only be evaluated at runtime, so `None` is returned.
+/// An operation on literals which overflows or divides by zero would always fail at runtime, so it is reported now instead.
+pub fn fold(
+    left: &Expression,
+    operator: Operator,
+    right: &Expression,
+    span: Span,
+) -> Result<Option<Expression>, Error> {
+    let (Some(left), Some(right)) = (value(left), value(right)) else {
+        return Ok(None);
+    };
+
+    let result = left.binary_operation(operator, right, span)?;
+    Ok(literal(result, span))
+}
+
+/// Returns the value of a literal expression.
+fn value(expression: &Expression) -> Option<Value> {
+    match expression {
+        Expression::IntegerLiteral(value, _) => Some(Value::Integer(*value)),
+        Expression::StringLiteral(value, _) => Some(Value::String(value.clone())),
+        Expression::BooleanLiteral(value, _) => Some(Value::Boolean(*value)),
+        _ => None,
+    }
+}
+
+/// Returns the literal expression for a value, at the [span] of the expression it replaces.
+fn literal(value: Value, span: Span) -> Option<Expression> {
+    match value {
+        Value::Integer(value) => Some(Expression::IntegerLiteral(value, span)),
+        Value::String(value) => Some(Expression::StringLiteral(value, span)),
+        Value::Boolean(value) => Some(Expression::BooleanLiteral(value, span)),
+        _ => None,
+    }
+}
@@ -1,37 +1,403 @@
-use std::collections::HashMap;
+pub mod folding;
+pub mod substitution;
+pub mod suggestion;
 
-use colored::Colorize;
+use std::collections::{HashMap, HashSet};
+
+use substitution::Substitution;
 
 pub use crate::visitor::*;
 
 use crate::{
-    ast::{Expression, Identifier, Statement},
-    interpreter::function::BuiltinFunctions,
-    position::Position,
-    r#type::Type,
-    Error, ErrorType,
+    ast::{
+        always_returns, Argument, Expression, Field, Function, Identifier, Implementation, Newtype, Operator,
+        Parameter, Pattern, Signature, Statement, Struct, Trait, TypeParameter,
+    },
+    interpreter::{function::BuiltinFunctions, method::BuiltinMethods},
+    lexer::Keyword,
+    r#type::{Constraint, GenericParameter, Type},
+    span::Span,
+    stack, Error, ErrorType,
 };
 
+/// The trait implemented by the types whose values can be added with `+`, which are integers and strings.
+/// It only exists to constrain the operands of `+` while their type is not known, so it cannot be named in source code.
+const ADDABLE: &str = "'Add";
+
 /// Resolves any unresolved or uninferred types.
-#[derive(Default)]
+/// Types are inferred Hindley-Milner style: anything without a declared type is given a type variable,
+/// which is bound by unifying it with the types it is used as.
 pub struct TypeResolver {
     variables: HashMap<Identifier, Type>,
+
+    /// Where each variable in scope was declared, and whether it can be modified.
+    declarations: HashMap<Identifier, Declaration>,
+
+    /// The values of the constants in scope, which are always literals.
+    /// Uses of a constant are replaced by its value, so that operations on it can be folded.
+    constants: HashMap<Identifier, Expression>,
+
     builtin_functions: BuiltinFunctions,
+    builtin_methods: BuiltinMethods,
+
+    /// The types of the methods that structs declare themselves, by the name of the struct and the name of the method.
+    methods: HashMap<(String, String), Type>,
+
+    /// The return type of the function currently being resolved, or `None` at the top level.
+    return_type: Option<Type>,
+
+    /// The structs that have been declared, by name.
+    structs: HashMap<String, Struct>,
+
+    /// The types that type aliases stand for, by the name of the alias.
+    aliases: HashMap<String, Type>,
+
+    /// The type aliases declared further on in the blocks currently being resolved, along with the types they are declared as.
+    /// They cannot be used until they are declared, but are followed to find aliases which refer to themselves.
+    pending_aliases: HashMap<String, (Identifier, Type)>,
+
+    /// The names of the type parameters that are currently in scope.
+    type_parameters: Vec<String>,
+
+    /// The traits that the type parameters currently in scope are known to implement, from their bounds.
+    assumptions: Vec<Constraint>,
+
+    /// The type that `Self` refers to, within a trait or an implementation.
+    self_type: Option<Type>,
+
+    /// The traits that have been declared, by name.
+    traits: HashMap<String, Trait>,
+
+    /// The types that each trait is implemented for, by the name of the trait and the name of the type.
+    /// Each type is generic over the type parameters of the implementation.
+    implementations: HashMap<(String, String), Type>,
+
+    /// Constraints on types which are not known yet, which are checked once they are.
+    constraints: Vec<(Constraint, Span)>,
+
+    /// The types that type variables have been inferred to be so far.
+    substitution: Substitution,
+
+    /// The number of type parameters that have been created by generalisation, used to give each a unique name.
+    generalised_parameters: usize,
+
+    /// The type variables that type parameters were instantiated with, which must have been inferred by the end.
+    instantiations: Vec<Instantiation>,
+
+    /// The types of let statements, which must have been inferred by the end.
+    bindings: Vec<(Type, Span)>,
+
+    /// The errors found in statements so far.
+    /// Resolving continues after a statement fails, so that every error in the program can be reported at once.
+    errors: Vec<Error>,
+
+    /// The warnings found so far, which do not stop the program from running unless their lint is denied.
+    warnings: Vec<Error>,
+
+    /// The variables declared by let statements, which are reported if they are never used.
+    let_bindings: Vec<Identifier>,
+
+    /// The spans of the declarations of every variable that has been used.
+    used: HashSet<Span>,
+
+    /// The function bodies being resolved, innermost last, along with the span of the name of each function declared by
+    /// a statement. The body of any other function, such as a lambda, can run as soon as the function is created.
+    bodies: Vec<Option<Span>>,
+
+    /// The functions declared further on in the blocks being resolved whose declarations have not been reached yet,
+    /// by the span of their name.
+    pending_functions: HashSet<Span>,
+
+    /// The functions used by the body of each function declared by a statement, by the span of its name,
+    /// along with the span of the name of each function used.
+    /// A function can use those declared after it, but cannot be called until their declarations have been reached.
+    function_uses: HashMap<Span, Vec<(Identifier, Span)>>,
+
+    /// The span of the name of the function declared by a statement, or of the method, whose body is resolved next.
+    next_body: Option<Span>,
+
+    /// The span of the name of each method implemented so far, along with the number of function bodies that it was
+    /// implemented inside of, by the method's name. Any of them could be the one called on a value.
+    implemented_methods: HashMap<String, Vec<(Span, usize)>>,
+}
+
+impl Default for TypeResolver {
+    fn default() -> Self {
+        let self_type = || Type::Parameter("Self".to_string());
+
+        // The built-in `Printable` trait is implemented by the built-in types, and can be implemented by structs.
+        // Its method is the built-in `display` function.
+        let printable = Trait {
+            identifier: Identifier::new("Printable".to_string(), Span::default()),
+            methods: vec![Signature {
+                identifier: Identifier::new("display".to_string(), Span::default()),
+                parameters: vec![Parameter {
+                    pattern: Pattern::Identifier(Identifier::new("self".to_string(), Span::default())),
+                    r#type: self_type(),
+                    default: None,
+                    variadic: false,
+                }],
+                return_type: Type::String,
+            }],
+            span: Span::default(),
+        };
+
+        // A list is printable if its elements are.
+        let printable_list = Type::Generic(
+            vec![GenericParameter::new("T".to_string(), vec!["Printable".to_string()])],
+            Box::new(Type::List(Box::new(Type::Parameter("T".to_string())))),
+        );
+
+        let implementation = |r#trait: &str, r#type: &str| (r#trait.to_string(), r#type.to_string());
+        let implementations = HashMap::from([
+            (implementation("Printable", "Integer"), Type::Integer),
+            (implementation("Printable", "String"), Type::String),
+            (implementation("Printable", "Boolean"), Type::Boolean),
+            (implementation("Printable", "List"), printable_list),
+            (implementation(ADDABLE, "Integer"), Type::Integer),
+            (implementation(ADDABLE, "String"), Type::String),
+        ]);
+
+        Self {
+            variables: HashMap::new(),
+            declarations: HashMap::new(),
+            constants: HashMap::new(),
+            builtin_functions: BuiltinFunctions,
+            builtin_methods: BuiltinMethods,
+            methods: HashMap::new(),
+            return_type: None,
+            structs: HashMap::new(),
+            aliases: HashMap::new(),
+            pending_aliases: HashMap::new(),
+            type_parameters: vec![],
+            assumptions: vec![],
+            self_type: None,
+            traits: HashMap::from([("Printable".to_string(), printable)]),
+            implementations,
+            constraints: vec![],
+            substitution: Substitution::default(),
+            generalised_parameters: 0,
+            instantiations: vec![],
+            bindings: vec![],
+            errors: vec![],
+            warnings: vec![],
+            let_bindings: vec![],
+            used: HashSet::new(),
+            bodies: vec![],
+            pending_functions: HashSet::new(),
+            function_uses: HashMap::new(),
+            next_body: None,
+            implemented_methods: HashMap::new(),
+        }
+    }
+}
+
+/// A variable whose type is narrowed within a branch of an if statement, along with the type it is narrowed to.
+type Narrowing = Option<(Identifier, Type)>;
+
+/// Where a variable was declared, and whether it can be modified through.
+#[derive(Clone)]
+struct Declaration {
+    /// The span of the variable's name where it was declared.
+    span: Span,
+
+    /// What declared the variable, which decides how it could be declared to allow it to be modified.
+    kind: DeclarationKind,
+
+    /// Whether the variable was declared with `let mut`.
+    mutable: bool,
+
+    /// Whether the variable was declared outside of the function currently being resolved.
+    /// Changes to such a variable would only change the function's own copy of it, so it cannot be modified.
+    captured: bool,
+
+    /// Whether the variable was declared outside of the block currently being resolved.
+    /// Declaring a variable with the same name shadows such a variable, but a variable can only be declared once per block.
+    outer: bool,
+
+    /// The number of function bodies that the variable was declared inside of.
+    depth: usize,
+
+    /// The type that the variable was declared with, if it has been narrowed to a more specific type since.
+    /// Values assigned to the variable can still be of any type it was declared with.
+    unnarrowed: Option<Type>,
+
+    /// Whether the variable is a function declared further on in the block currently being resolved.
+    /// Functions can call it before then, but the block itself cannot use it until its declaration has run.
+    hoisted: bool,
+
+    /// The parameters of the function that the variable was declared as, if it was declared by a function declaration.
+    /// Only such functions can be called with arguments passed by name, or without arguments for parameters with defaults.
+    parameters: Option<Vec<Parameter>>,
+}
+
+/// What declared a variable.
+#[derive(Clone, Copy, PartialEq)]
+enum DeclarationKind {
+    /// A let statement, which can be declared with `let mut` instead.
+    Let,
+
+    /// A constant, which can be declared as a variable with `let mut` instead.
+    Constant,
+
+    /// A function declaration, a trait method or a newtype's constructor, none of which can be modified.
+    Function,
+
+    /// A parameter or any other binding in a pattern, which can be copied into a variable declared with `let mut`.
+    Binding,
+}
+
+/// A type parameter of a generic function or struct, which was instantiated with a type variable when it was used.
+struct Instantiation {
+    variable: Type,
+    parameter: String,
+    name: String,
+    span: Span,
 }
 
 impl StatementVisitor<Statement> for TypeResolver {
+    /// Resolves the statements of a program, returning only the first error.
+    /// Use [TypeResolver::resolve] to find every error.
+    fn visit_statements(&mut self, statements: Vec<Statement>) -> Result<Vec<Statement>, Error> {
+        self.resolve(statements).map_err(|mut errors| errors.remove(0))
+    }
+
     fn visit_statement(&mut self, statement: Statement) -> Result<Statement, Error> {
         match statement {
             Statement::Let {
+                pattern,
+                value,
+                r#type,
+                type_span,
+                mutable,
+                span,
+            } => self.visit_let_statement(pattern, value, r#type, type_span, mutable, span),
+
+            Statement::Assignment { place, value, span } => {
+                if !place.is_place() {
+                    return Err(Error::new(ErrorType::NotAssignable, span));
+                }
+
+                // Constants are replaced by their values when they are resolved, so the place is checked beforehand.
+                self.check_mutable(&place)?;
+
+                // The variable could be given a value of any type it was declared with, so it is no longer narrowed.
+                if let Expression::Identifier(_, identifier) = &place {
+                    self.widen(identifier);
+                }
+
+                let place = self.visit_expression(place)?;
+                let value = self.visit_expression(value)?;
+                let value = self.cast(&place.r#type(), value)?;
+
+                Ok(Statement::Assignment { place, value, span })
+            },
+
+            Statement::Constant {
                 identifier,
                 value,
+                span,
+            } => {
+                // The value of a constant is folded into a literal while it is resolved.
+                let value = self.visit_expression(value)?;
+                if !value.is_literal() {
+                    return Err(Error::new(ErrorType::NotConstant, span));
+                }
+
+                self.declare_variable(identifier.clone(), value.r#type(), DeclarationKind::Constant);
+                self.constants.insert(identifier.clone(), value.clone());
+
+                Ok(Statement::Constant {
+                    identifier,
+                    value,
+                    span,
+                })
+            },
+
+            Statement::Return { value, span } => self.visit_return_statement(value, span),
+
+            Statement::If {
+                condition,
+                then_branch,
+                else_branch,
+                span,
+            } => self.visit_if_statement(condition, then_branch, else_branch, span),
+
+            Statement::Function(function) => {
+                let identifier = function.identifier.clone().expect("function statements should always be named");
+                self.pending_functions.remove(&identifier.span);
+                self.next_body = Some(identifier.span);
+
+                let function = self.visit_function(function)?;
+
+                // Any calls made before the declaration was reached must agree with the function's actual type.
+                // The hoisted type is no longer in scope, so that the function's own type variables can be generalised.
+                if let Some(hoisted) = self.hoisted_type(&identifier) {
+                    self.variables.remove(&identifier);
+                    self.unify(&hoisted, &function.r#type().without_type_parameters(), function.span)?;
+                }
+
+                // A function declaration can be used with different types each time it is called.
+                let r#type = self.generalise(function.r#type())?;
+                self.declare_function(identifier, r#type, function.parameters.clone());
+
+                Ok(Statement::Function(function))
+            },
+
+            Statement::Struct(r#struct) => {
+                let r#struct = self.visit_struct(r#struct)?;
+                Ok(Statement::Struct(r#struct))
+            },
+
+            Statement::TypeAlias {
+                identifier,
                 r#type,
-                position,
-            } => self.visit_let_statement(identifier, value, r#type, position),
+                span,
+            } => {
+                // The other aliases in a cycle that has been reported stand for an error, like the alias it was reported for.
+                if let Some((_, Type::Error)) = self.pending_aliases.remove(&identifier.name) {
+                    self.aliases.insert(identifier.name.clone(), Type::Error);
+
+                    return Ok(Statement::TypeAlias {
+                        identifier,
+                        r#type: Type::Error,
+                        span,
+                    });
+                }
+
+                self.check_type_name(&identifier)?;
+
+                let resolved = self
+                    .check_alias_cycle(&identifier, &r#type)
+                    .and_then(|_| self.resolve_declared_type(r#type, identifier.span));
+
+                // An alias which cannot be resolved stands for an error, so that its uses are not reported as well.
+                let r#type = resolved.inspect_err(|_| {
+                    self.aliases.insert(identifier.name.clone(), Type::Error);
+                })?;
+
+                self.aliases.insert(identifier.name.clone(), r#type.clone());
+
+                Ok(Statement::TypeAlias {
+                    identifier,
+                    r#type,
+                    span,
+                })
+            },
+
+            Statement::Newtype(newtype) => {
+                let newtype = self.visit_newtype(newtype)?;
+                Ok(Statement::Newtype(newtype))
+            },
+
+            Statement::Trait(r#trait) => {
+                let r#trait = self.visit_trait(r#trait)?;
+                Ok(Statement::Trait(r#trait))
+            },
 
-            Statement::Return { value, position } => {
-                let value = value.map(|value| self.visit_expression(value)).transpose()?;
-                Ok(Statement::Return { value, position })
+            Statement::Implementation(implementation) => {
+                let implementation = self.visit_implementation(implementation)?;
+                Ok(Statement::Implementation(implementation))
             },
 
             Statement::Expression(expression) => {
@@ -44,102 +410,436 @@ impl StatementVisitor<Statement> for TypeResolver {
 
 impl ExpressionVisitor<Expression> for TypeResolver {
     fn visit_expression(&mut self, expression: Expression) -> Result<Expression, Error> {
+        if stack::is_exhausted() {
+            return Err(Error::new(ErrorType::NestedTooDeeply, expression.span())
+                .with_help("split it up, e.g. by storing parts of it in variables"));
+        }
+
         match expression {
-            Expression::IntegerLiteral(_) => Ok(expression),
-            Expression::StringLiteral(_) => Ok(expression),
+            Expression::IntegerLiteral(..) => Ok(expression),
+            Expression::StringLiteral(..) => Ok(expression),
+            Expression::BooleanLiteral(..) => Ok(expression),
+
+            Expression::Is { value, target, span } => {
+                let value = self.visit_expression(*value)?;
+                let target = self.resolve_declared_type(target, span)?;
+
+                // A value can only be checked against a type that it could be.
+                match self.substitution.apply(&value.r#type()) {
+                    Type::Union(members) if members.contains(&target) => {},
+                    Type::Any if target.is_checkable() => {},
+                    Type::Any => return Err(Error::new(ErrorType::UncheckableType(target), span)),
+                    r#type if r#type == target => {},
+                    Type::Error => {},
+                    Type::Variable(_) => return Err(Error::new(ErrorType::UnableToInferType, span)),
+                    r#type => return Err(Error::new(ErrorType::ImpossibleTypeCheck(r#type, target), span)),
+                }
+
+                Ok(Expression::Is {
+                    value: Box::new(value),
+                    target,
+                    span,
+                })
+            },
+
+            Expression::Cast { value, target, span } => {
+                let value = self.visit_expression(*value)?;
+                let target = self.resolve_declared_type(target, span)?;
+
+                // A cast out of `Any` or a union is checked at runtime, while any other cast must always succeed.
+                match self.substitution.apply(&value.r#type()) {
+                    Type::Any | Type::Union(_) if !target.is_checkable() => {
+                        Err(Error::new(ErrorType::UncheckableType(target.clone()), span))
+                    },
+                    Type::Any | Type::Error => Ok(()),
+                    Type::Union(members) if members.contains(&target) => Ok(()),
+                    r#type => self.coerce(&target, &r#type, span),
+                }?;
+
+                Ok(Expression::Cast {
+                    value: Box::new(value),
+                    target,
+                    span,
+                })
+            },
 
             Expression::BinaryOperation {
                 left,
                 right,
-                position,
+                span,
                 operator,
                 ..
             } => {
-                // If the type is unresolved, and can be resolved, resolve it.
-                let left_type = self.visit_expression(*left.clone())?.r#type();
-                let right_type = self.visit_expression(*right.clone())?.r#type();
+                let left = self.visit_expression(*left)?;
+                let right = self.visit_expression(*right)?;
+
+                // An operand of type `Any` is checked to be of the same type as the other operand when the program runs.
+                let left = self.cast_operand(operator, left, &right)?;
+                let right = self.cast_operand(operator, right, &left)?;
+
+                // Operators are not defined for unions, even if they are defined for each of their members.
+                for operand in [&left, &right] {
+                    if let r#type @ Type::Union(_) = self.substitution.apply(&operand.r#type()) {
+                        return Err(Error::new(ErrorType::UnnarrowedUnion(r#type), span));
+                    }
+                }
 
-                // Ensure that the type of the value matches the type of the variable.
-                if left_type != right_type {
-                    return Err(Error::new(ErrorType::TypeMismatch(left_type, right_type), position));
+                // Both sides of a binary operation must be of the same type.
+                self.unify(&left.r#type(), &right.r#type(), span)?;
+
+                // Strings can only be added together, and every other operation requires integers.
+                // If the type of the operands is not known yet, they are assumed to be integers, unless they are added,
+                // in which case they could be either.
+                let r#type = match (operator, self.substitution.apply(&left.r#type())) {
+                    (Operator::Add, Type::String) => Type::String,
+                    (_, Type::Integer) => Type::Integer,
+                    (_, Type::Error) => Type::Error,
+
+                    (Operator::Add, r#type @ Type::Variable(_)) => {
+                        self.require(Constraint::new(r#type.clone(), ADDABLE.to_string()), span)?;
+                        r#type
+                    },
+
+                    (_, r#type @ Type::Variable(_)) => {
+                        self.unify(&Type::Integer, &r#type, span)?;
+                        Type::Integer
+                    },
+
+                    (_, r#type) => {
+                        return Err(Error::new(ErrorType::UnsupportedOperator(operator, r#type), span));
+                    },
+                };
+
+                // An operation on literals is evaluated now, rather than each time it is run.
+                if let Some(literal) = folding::fold(&left, operator, &right, span)? {
+                    return Ok(literal);
                 }
 
                 Ok(Expression::BinaryOperation {
-                    left,
+                    left: Box::new(left),
                     operator,
-                    position,
-                    right,
-                    r#type: left_type,
+                    span,
+                    right: Box::new(right),
+                    r#type,
                 })
             },
 
             Expression::Identifier(.., identifier) => {
+                if let Some(value) = self.constants.get(&identifier) {
+                    return Ok(value.clone());
+                }
+
                 // Identifiers have no type attached to them, so we need to look up the type in the variables map.
-                let resolved_type = self
+                let declared_type = self
                     .variables
                     .get(&identifier)
-                    .ok_or(Error::new(
-                        ErrorType::UnknownVariable(identifier.clone().name),
-                        identifier.position,
-                    ))?
+                    .filter(|_| self.is_declared(&identifier))
+                    .ok_or_else(|| {
+                        let error = Error::new(ErrorType::UnknownVariable(identifier.name.clone()), identifier.span);
+                        self.suggest_name(error, &identifier.name, true)
+                    })?
                     .clone();
 
-                println!(
-                    "{}: resolved type of `{}` to `{:?}`",
-                    "info(resolver)".blue(),
+                if let Some(declaration) = self.declarations.get(&identifier).cloned() {
+                    self.used.insert(declaration.span);
+
+                    if declaration.kind == DeclarationKind::Function {
+                        self.use_function(&identifier, declaration.span, declaration.depth)?;
+                    }
+
+                    // A function only sees the value a mutable variable had when the function was created.
+                    if declaration.captured && declaration.mutable && declared_type != Type::Error {
+                        let error_type = ErrorType::CapturedMutableVariable(identifier.name.clone());
+                        let note =
+                            "functions capture the values of variables when they are created, so changes are missed";
+
+                        return Err(Error::new(error_type, identifier.span)
+                            .with_label(declaration.span, format!("`{}` is declared here", identifier.name))
+                            .with_note(note)
+                            .with_help("pass it to the function as an argument instead"));
+                    }
+                }
+
+                // Each use of a generic value gets its own instance of the value's type.
+                let resolved_type = self.instantiate(declared_type, &identifier.name, identifier.span)?;
+
+                log::trace!(
+                    "resolved type of `{}` to `{}`",
                     identifier.name,
-                    resolved_type
+                    self.substitution.apply(&resolved_type)
                 );
 
                 Ok(Expression::Identifier(resolved_type, identifier))
             },
 
             Expression::FunctionCall {
-                identifier, arguments, ..
+                callee,
+                arguments,
+                span,
+                ..
             } => {
                 // Resolve the types of any expressions passed as arguments.
-                let mut expressions = Vec::new();
+                let mut resolved = Vec::new();
                 for argument in arguments {
-                    expressions.push(self.visit_expression(argument)?);
+                    resolved.push(Argument {
+                        name: argument.name,
+                        value: self.visit_expression(argument.value)?,
+                    });
                 }
 
-                // TODO: Add support for code-defined functions.
-                let function = self.builtin_functions.get(&identifier.name).ok_or(Error::new(
-                    ErrorType::UnknownFunction(identifier.name.clone()),
-                    identifier.position,
-                ))?;
+                // Built-in functions are called by name, unless a variable with the same name is in scope.
+                if let Expression::Identifier(_, identifier) = callee.as_ref() {
+                    if !self.is_declared(identifier) {
+                        let function = self.builtin_functions.get(&identifier.name).ok_or_else(|| {
+                            let error =
+                                Error::new(ErrorType::UnknownFunction(identifier.name.clone()), identifier.span);
+                            self.suggest_name(error, &identifier.name, false)
+                        })?;
 
-                // Ensure that the number of arguments matches the number of arguments the function takes.
-                if expressions.len() != function.arguments().len() {
-                    return Err(Error::new(
-                        ErrorType::InvalidNumberOfArguments(function.arguments().len(), expressions.len()),
-                        identifier.position,
-                    ));
-                }
+                        let Type::Function(parameters, return_type) =
+                            self.instantiate(function.r#type(), &identifier.name, identifier.span)?
+                        else {
+                            unreachable!("built-in functions should always have a function type");
+                        };
 
-                // Ensure that the types of the arguments match the types of the arguments the function takes.
-                for (index, expression) in expressions.iter().enumerate() {
-                    // This is safe because we already checked that the number of arguments matches the number of arguments the function takes.
-                    let expected_type = function.arguments().get(index).unwrap().clone();
-                    let actual_type = expression.r#type();
+                        // A built-in function whose last argument type is `Void` takes any number of further arguments.
+                        let variadic = parameters.last() == Some(&Type::Void);
 
-                    // If the expected type is `Any`, then we can skip the type check.
-                    if expected_type == Type::Any {
-                        continue;
-                    }
+                        let mut declared = function
+                            .argument_names()
+                            .into_iter()
+                            .map(|name| Parameter {
+                                pattern: Pattern::Identifier(Identifier::new(name.to_string(), identifier.span)),
+                                r#type: Type::default(),
+                                default: None,
+                                variadic: false,
+                            })
+                            .collect::<Vec<_>>();
+
+                        if variadic {
+                            declared.push(Parameter {
+                                pattern: Pattern::Identifier(Identifier::new("...".to_string(), identifier.span)),
+                                r#type: Type::default(),
+                                default: None,
+                                variadic: true,
+                            });
+                        }
+
+                        let (mut expressions, rest) = self.match_arguments(&declared, resolved, identifier.span)?;
+
+                        // Each further argument is checked against its own instance of the type of the argument before it,
+                        // so that e.g. `print` can be given values of different types.
+                        let mut parameters = parameters;
+                        if variadic {
+                            parameters.pop();
+
+                            for _ in &rest {
+                                let Type::Function(instance, _) =
+                                    self.instantiate(function.r#type(), &identifier.name, identifier.span)?
+                                else {
+                                    unreachable!("built-in functions should always have a function type");
+                                };
 
-                    if actual_type != expected_type {
-                        return Err(Error::new(
-                            ErrorType::TypeMismatch(actual_type, expected_type),
-                            identifier.position,
-                        ));
+                                parameters.push(instance[instance.len() - 2].clone());
+                            }
+
+                            expressions.extend(rest);
+                        }
+
+                        let expressions = self.check_arguments(&parameters, expressions, identifier.span)?;
+
+                        return Ok(Expression::FunctionCall {
+                            callee,
+                            arguments: expressions.into_iter().map(Argument::positional).collect(),
+                            span,
+                            r#type: *return_type,
+                            tail_call: false,
+                        });
                     }
                 }
 
+                // A function declared by name can be called with named arguments, and without arguments that have defaults.
+                let declared = match callee.as_ref() {
+                    Expression::Identifier(_, identifier) => {
+                        self.declarations.get(identifier).and_then(|it| it.parameters.clone())
+                    },
+
+                    _ => None,
+                };
+
+                let expressions = match declared {
+                    Some(parameters) => {
+                        let (mut expressions, rest) = self.match_arguments(&parameters, resolved, span)?;
+
+                        if parameters.last().is_some_and(|it| it.variadic) {
+                            expressions.push(self.variadic_list(rest, span)?);
+                        }
+
+                        expressions
+                    },
+
+                    None => Self::positional_arguments(resolved)?,
+                };
+
+                // Otherwise, any expression of a function type can be called.
+                let callee = self.visit_expression(*callee)?;
+
+                let (parameters, return_type) = match self.substitution.apply(&callee.r#type()) {
+                    Type::Function(parameters, return_type) => (parameters, *return_type),
+
+                    // If the type of the callee is not known yet, it must be a function taking these arguments.
+                    r#type @ Type::Variable(_) => {
+                        let parameters = expressions.iter().map(|_| self.substitution.fresh()).collect::<Vec<_>>();
+                        let return_type = self.substitution.fresh();
+
+                        let function_type = Type::Function(parameters.clone(), Box::new(return_type.clone()));
+                        self.unify(&function_type, &r#type, span)?;
+
+                        (parameters, return_type)
+                    },
+
+                    Type::Error => (expressions.iter().map(|_| Type::Error).collect(), Type::Error),
+                    r#type => return Err(Error::new(ErrorType::NotCallable(r#type), span)),
+                };
+
+                let expressions = self.check_arguments(&parameters, expressions, span)?;
+
                 Ok(Expression::FunctionCall {
-                    identifier,
-                    arguments: expressions,
-                    r#type: function.return_type(),
+                    callee: Box::new(callee),
+                    arguments: expressions.into_iter().map(Argument::positional).collect(),
+                    span,
+                    r#type: return_type,
+                    tail_call: false,
+                })
+            },
+
+            Expression::Function(function) => {
+                let function = self.visit_function(function)?;
+                Ok(Expression::Function(function))
+            },
+
+            Expression::List { elements, span, .. } => {
+                let element_type = self.substitution.fresh();
+
+                let mut expressions = Vec::new();
+                for element in elements {
+                    expressions.push(self.visit_expression(element)?);
+                }
+
+                // Elements of type `Any` are checked to be of the same type as the other elements when the program runs,
+                // and the list only has elements of type `Any` if every element is.
+                let is_any =
+                    |resolver: &Self, element: &Expression| resolver.substitution.apply(&element.r#type()) == Type::Any;
+                for element in &expressions {
+                    if !is_any(self, element) {
+                        self.unify(&element_type, &element.r#type(), element.span())?;
+                    }
+                }
+
+                let expressions = expressions
+                    .into_iter()
+                    .map(|element| match is_any(self, &element) {
+                        true => self.cast(&element_type, element),
+                        false => Ok(element),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                if !expressions.is_empty() && expressions.iter().all(|element| is_any(self, element)) {
+                    self.unify(&element_type, &Type::Any, span)?;
+                }
+
+                Ok(Expression::List {
+                    elements: expressions,
+                    span,
+                    r#type: Type::List(Box::new(element_type)),
+                })
+            },
+
+            Expression::Tuple { elements, span, .. } => {
+                let mut expressions = Vec::new();
+                for element in elements {
+                    expressions.push(self.visit_expression(element)?);
+                }
+
+                Ok(Expression::Tuple {
+                    r#type: Type::Tuple(expressions.iter().map(Expression::r#type).collect()),
+                    elements: expressions,
+                    span,
+                })
+            },
+
+            Expression::TupleAccess { value, index, span, .. } => {
+                let value = self.visit_expression(*value)?;
+
+                // Unlike a list, the number of elements in a tuple must be known to access one of them.
+                let r#type = match self.substitution.apply(&value.r#type()) {
+                    Type::Tuple(elements) if index < elements.len() => elements[index].clone(),
+                    Type::Error => Type::Error,
+                    Type::Variable(_) => return Err(Error::new(ErrorType::UnableToInferType, span)),
+                    r#type => return Err(Error::new(ErrorType::NoTupleElement(r#type, index), span)),
+                };
+
+                Ok(Expression::TupleAccess {
+                    value: Box::new(value),
+                    index,
+                    span,
+                    r#type,
+                })
+            },
+
+            Expression::Index { value, index, span, .. } => {
+                let value = self.visit_expression(*value)?;
+                let index = self.visit_expression(*index)?;
+
+                let element_type = match self.substitution.apply(&value.r#type()) {
+                    Type::List(element_type) => *element_type,
+                    Type::Error => Type::Error,
+
+                    // If the type of the value is not known yet, it must be a list.
+                    r#type @ Type::Variable(_) => {
+                        let element_type = self.substitution.fresh();
+                        self.unify(&Type::List(Box::new(element_type.clone())), &r#type, span)?;
+
+                        element_type
+                    },
+
+                    r#type => return Err(Error::new(ErrorType::NotIndexable(r#type), span)),
+                };
+
+                self.unify(&Type::Integer, &index.r#type(), index.span())?;
+
+                Ok(Expression::Index {
+                    value: Box::new(value),
+                    index: Box::new(index),
+                    span,
+                    r#type: element_type,
+                })
+            },
+
+            Expression::StructLiteral {
+                identifier,
+                fields,
+                span,
+                ..
+            } => self.visit_struct_literal(identifier, fields, span),
+
+            Expression::MethodCall {
+                value,
+                method,
+                arguments,
+                span,
+                ..
+            } => self.visit_method_call(*value, method, arguments, span),
+
+            Expression::FieldAccess { value, field, span, .. } => {
+                let value = self.visit_expression(*value)?;
+                let r#type = self.field_type(&value.r#type(), &field)?;
+
+                Ok(Expression::FieldAccess {
+                    value: Box::new(value),
+                    field,
+                    span,
+                    r#type,
                 })
             },
         }
@@ -147,54 +847,2144 @@ impl ExpressionVisitor<Expression> for TypeResolver {
 }
 
 impl TypeResolver {
-    fn visit_let_statement(
-        &mut self,
-        identifier: Identifier,
-        value: Expression,
-        r#type: Type,
-        position: Position,
-    ) -> Result<Statement, Error> {
-        // First, resolve the type of the value.
-        let value = self.visit_expression(value)?;
+    /// Resolves the statements of a program, returning every error that was found.
+    /// Once every statement has been resolved, any types that could not be inferred are reported,
+    /// and the inferred types are filled in.
+    pub fn resolve(&mut self, statements: Vec<Statement>) -> Result<Vec<Statement>, Vec<Error>> {
+        let statements = self.visit_block(statements);
+
+        // Types often cannot be inferred because of an earlier error, so they are only reported if there are no others.
+        // The same goes for variables that are never used, as the statement that used them may have failed.
+        if self.errors.is_empty() {
+            if let Err(error) = self.check_inferred() {
+                self.errors.push(error);
+            }
+
+            self.check_used();
+        }
+
+        if !self.errors.is_empty() {
+            return Err(std::mem::take(&mut self.errors));
+        }
+
+        Ok(statements
+            .into_iter()
+            .map(|statement| statement.map_types(&mut |it| self.substitution.apply(&it)))
+            .collect())
+    }
+
+    /// Returns the warnings found while resolving, in the order they appear in the source code.
+    pub fn warnings(&mut self) -> Vec<Error> {
+        let mut warnings = std::mem::take(&mut self.warnings);
+        warnings.sort_by_key(|it| it.span.start);
+
+        warnings
+    }
 
-        // If the type is unresolved, and can be resolved, resolve it.
-        let mut resolved_type = Self::resolve_type(r#type, position)?;
+    /// Warns about any variables declared by let statements which were never used.
+    /// A variable whose name starts with an underscore is meant to be unused, so it is never reported.
+    fn check_used(&mut self) {
+        for identifier in &self.let_bindings {
+            if !identifier.name.starts_with('_') && !self.used.contains(&identifier.span) {
+                let warning = Error::warning(ErrorType::UnusedVariable(identifier.name.clone()), identifier.span)
+                    .with_help(format!(
+                        "remove the variable, name it `_{}`, or allow this warning with `// cecelia:allow(unused)`",
+                        identifier.name
+                    ));
 
-        // If the resolved type still needs to be inferred, infer it from the value type.
-        if let Type::Unresolved(None) = resolved_type {
-            // If the value type still needs to be inferred, then we can't infer the type of the variable.
-            if let Type::Unresolved(None) = value.r#type() {
-                return Err(Error::new(ErrorType::UnableToInferType, position));
+                self.warnings.push(warning);
             }
+        }
+    }
+
+    /// Ensures that every type that must be known by the end of the program was inferred.
+    fn check_inferred(&mut self) -> Result<(), Error> {
+        self.solve_constraints()?;
 
-            resolved_type = value.r#type();
+        // Declared type parameters give the clearest message, followed by variables, and then generalised type parameters,
+        // whose names cannot be written in source code.
+        let (declared, generalised): (Vec<_>, Vec<_>) =
+            self.instantiations.iter().partition(|it| !it.parameter.starts_with('\''));
+
+        for instantiation in declared {
+            self.check_instantiation(instantiation)?;
         }
 
-        // Ensure that the type of the value matches the type of the variable.
-        if resolved_type != value.r#type() {
-            return Err(Error::new(
-                ErrorType::TypeMismatch(resolved_type, value.r#type()),
-                position,
-            ));
+        for (r#type, span) in &self.bindings {
+            if !self.substitution.apply(r#type).variables().is_empty() {
+                return Err(Error::new(ErrorType::UnableToInferType, *span));
+            }
         }
 
-        self.variables.insert(identifier.clone(), resolved_type.clone());
+        for instantiation in generalised {
+            self.check_instantiation(instantiation)?;
+        }
 
-        Ok(Statement::Let {
-            identifier,
-            value,
-            r#type: resolved_type,
-            position,
-        })
+        if let Some((_, span)) = self.constraints.first() {
+            return Err(Error::new(ErrorType::UnableToInferType, *span));
+        }
+
+        Ok(())
     }
 
-    fn resolve_type(r#type: Type, last_position: Position) -> Result<Type, Error> {
-        // If the type is unresolved, and can be resolved, resolve it.
-        if let Type::Unresolved(Some(type_name)) = r#type {
-            Err(Error::new(ErrorType::UnableToResolveType(type_name), last_position))
-        } else {
-            // Otherwise, return the type as is.
-            Ok(r#type)
+    /// Ensures that the type parameter of an [instantiation] was inferred.
+    fn check_instantiation(&self, instantiation: &Instantiation) -> Result<(), Error> {
+        if self.substitution.apply(&instantiation.variable).variables().is_empty() {
+            return Ok(());
         }
+
+        Err(Error::new(
+            ErrorType::UnableToInferTypeParameter(instantiation.parameter.clone(), instantiation.name.clone()),
+            instantiation.span,
+        ))
+    }
+
+    /// Resolves a list of statements, without reporting any types that could not be inferred yet.
+    /// A statement that fails to resolve is recorded as an error, and resolving continues with the next statement.
+    fn visit_block(&mut self, statements: Vec<Statement>) -> Vec<Statement> {
+        self.check_reachable(&statements);
+        self.hoist_functions(&statements);
+        self.hoist_aliases(&statements);
+
+        let mut resolved = Vec::new();
+
+        for statement in statements {
+            let span = statement.span();
+            let returns = always_returns(std::slice::from_ref(&statement));
+            let declared = Self::declared_identifiers(&statement);
+
+            match self.visit_statement(statement) {
+                Ok(statement) => resolved.push(statement),
+
+                Err(error) => {
+                    self.errors.push(error);
+
+                    // Anything the statement declares is still declared, so that its uses are not reported as unknown.
+                    for identifier in declared {
+                        self.declare_variable(identifier.clone(), Type::Error, DeclarationKind::Binding);
+
+                        if let Some(declaration) = self.declarations.get_mut(&identifier) {
+                            declaration.mutable = true;
+                        }
+                    }
+
+                    // A return statement is kept, so that the function is not also reported as missing one.
+                    if returns {
+                        resolved.push(Statement::Return { value: None, span });
+                    }
+                },
+            }
+        }
+
+        resolved
+    }
+
+    /// Declares the functions declared by a block before any of its statements are resolved,
+    /// so that functions can call those declared after them, including each other.
+    /// The type of a function whose signature is not fully declared is inferred from those calls, until it is declared.
+    fn hoist_functions(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            let Statement::Function(function) = statement else {
+                continue;
+            };
+
+            let identifier = function.identifier.clone().expect("function statements should always be named");
+
+            // A signature which cannot be resolved is reported when the function itself is resolved.
+            let type_parameters = function.type_parameters.clone();
+            let Ok(signature) = self.with_type_parameters(&type_parameters, |resolver| {
+                resolver.resolve_signature(function.clone())
+            }) else {
+                continue;
+            };
+
+            self.declare_function(identifier.clone(), signature.r#type(), signature.parameters);
+            self.pending_functions.insert(identifier.span);
+
+            if let Some(declaration) = self.declarations.get_mut(&identifier) {
+                declaration.hoisted = true;
+            }
+        }
+    }
+
+    /// Records the type aliases declared by a block before any of its statements are resolved,
+    /// so that an alias which refers to itself through those declared after it is reported as a cycle.
+    fn hoist_aliases(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            if let Statement::TypeAlias { identifier, r#type, .. } = statement {
+                self.pending_aliases.insert(identifier.name.clone(), (identifier.clone(), r#type.clone()));
+            }
+        }
+    }
+
+    /// Ensures that the type that an alias is declared as does not refer to the alias itself,
+    /// either directly or through the aliases declared after it.
+    fn check_alias_cycle(&mut self, identifier: &Identifier, r#type: &Type) -> Result<(), Error> {
+        let Some(cycle) = self.alias_cycle(&identifier.name, r#type, &mut HashSet::new()) else {
+            return Ok(());
+        };
+
+        for alias in &cycle {
+            self.pending_aliases.insert(alias.name.clone(), (alias.clone(), Type::Error));
+        }
+
+        let error = Error::new(ErrorType::CyclicTypeAlias(identifier.name.clone()), identifier.span)
+            .with_note("an alias is only another name for its type, so it cannot be part of it")
+            .with_help("declare a newtype instead, which can refer to itself within a list");
+
+        Err(cycle.into_iter().fold(error, |error, alias| {
+            error.with_label(
+                alias.span,
+                format!("`{}` refers back to `{}`", alias.name, identifier.name),
+            )
+        }))
+    }
+
+    /// Returns the aliases declared later on that a [type] refers to [name] through, if it refers to it at all.
+    fn alias_cycle(&self, name: &str, r#type: &Type, visited: &mut HashSet<String>) -> Option<Vec<Identifier>> {
+        for referenced in r#type.names() {
+            if referenced == name {
+                return Some(vec![]);
+            }
+
+            if !visited.insert(referenced.to_string()) {
+                continue;
+            }
+
+            if let Some((alias, aliased)) = self.pending_aliases.get(referenced) {
+                if let Some(mut cycle) = self.alias_cycle(name, aliased, visited) {
+                    cycle.insert(0, alias.clone());
+                    return Some(cycle);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Records a use of a function, whose name is declared at [declaration] inside [depth] function bodies.
+    /// A use within the body of a function declared by a statement alongside it only runs once that function is called,
+    /// so it is checked when that function is used instead. Any other use can run straight away.
+    fn use_function(&mut self, identifier: &Identifier, declaration: Span, depth: usize) -> Result<(), Error> {
+        match self.bodies.get(depth) {
+            Some(Some(function)) => {
+                let uses = self.function_uses.entry(*function).or_default();
+                uses.push((identifier.clone(), declaration));
+                Ok(())
+            },
+
+            _ => self.check_functions_declared(identifier, declaration),
+        }
+    }
+
+    /// Records that the method called [identifier] is being implemented, and that its body is resolved next.
+    fn implement_method(&mut self, identifier: &Identifier) {
+        let methods = self.implemented_methods.entry(identifier.name.clone()).or_default();
+        methods.push((identifier.span, self.bodies.len()));
+        self.next_body = Some(identifier.span);
+    }
+
+    /// Ensures that the function used by [identifier], and every function that it uses in turn, has been declared,
+    /// as the use can call it straight away.
+    fn check_functions_declared(&self, identifier: &Identifier, declaration: Span) -> Result<(), Error> {
+        let mut functions = vec![(identifier.clone(), declaration)];
+        let mut visited = HashSet::new();
+
+        while let Some((function, span)) = functions.pop() {
+            if self.pending_functions.contains(&span) {
+                let error = Error::new(ErrorType::UnknownFunction(function.name.clone()), identifier.span)
+                    .with_label(span, format!("`{}` is declared here", function.name));
+
+                if function.name == identifier.name {
+                    return Err(error);
+                }
+
+                return Err(error.with_note(format!(
+                    "`{}` uses `{}`, which cannot be called until its declaration has been reached",
+                    identifier.name, function.name
+                )));
+            }
+
+            if visited.insert(span) {
+                functions.extend(self.function_uses.get(&span).into_iter().flatten().cloned());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the type that a function was hoisted with, without its declared type parameters.
+    fn hoisted_type(&self, identifier: &Identifier) -> Option<Type> {
+        self.declarations.get(identifier).filter(|it| it.hoisted)?;
+        self.variables.get(identifier).map(Type::without_type_parameters)
+    }
+
+    /// Whether a variable can be used where it is referred to by [identifier].
+    /// A hoisted function can only be used by the block declaring it once its declaration has been reached,
+    /// although the functions within the block can use it at any point.
+    fn is_declared(&self, identifier: &Identifier) -> bool {
+        match self.declarations.get(identifier) {
+            Some(declaration) => !declaration.hoisted || declaration.captured,
+            None => self.variables.contains_key(identifier),
+        }
+    }
+
+    /// Warns about any statements in a block that follow a statement which always returns, as they can never run.
+    fn check_reachable(&mut self, statements: &[Statement]) {
+        let Some(index) = statements.iter().position(|it| always_returns(std::slice::from_ref(it))) else {
+            return;
+        };
+
+        if let (Some(first), Some(last)) = (statements.get(index + 1), statements.last()) {
+            let warning = Error::warning(ErrorType::UnreachableCode, first.span().to(last.span()))
+                .with_label(statements[index].span(), "any code after this statement is unreachable");
+
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Returns the variables that a [statement] declares in the scope it appears in.
+    fn declared_identifiers(statement: &Statement) -> Vec<Identifier> {
+        match statement {
+            Statement::Let { pattern, .. } => pattern.identifiers().into_iter().cloned().collect(),
+            Statement::Constant { identifier, .. } => vec![identifier.clone()],
+            Statement::Function(function) => function.identifier.clone().into_iter().collect(),
+            Statement::Newtype(newtype) => vec![newtype.identifier.clone()],
+            _ => vec![],
+        }
+    }
+
+    fn visit_let_statement(
+        &mut self,
+        pattern: Pattern,
+        value: Expression,
+        r#type: Type,
+        type_span: Option<Span>,
+        mutable: bool,
+        span: Span,
+    ) -> Result<Statement, Error> {
+        // If the variable has no declared type, it is inferred from the value.
+        let declared_type = self.resolve_type(r#type, type_span.unwrap_or(span))?;
+        let value = self.visit_expected_expression(value, &declared_type)?;
+
+        // Ensure that the type of the value matches the type of the variable.
+        let value = self.cast(&declared_type, value).map_err(|error| match type_span {
+            Some(type_span) => {
+                let declared_type = self.substitution.apply(&declared_type);
+                error.with_label(
+                    type_span,
+                    format!("expected because `{}` is declared as `{}`", pattern, declared_type),
+                )
+            },
+            None => error,
+        })?;
+
+        // Only functions and other variables are generalised, as generalising any other value (e.g. the result of a
+        // function call) would allow a single value to be treated as if it was multiple different types.
+        // A variable's type is only generic over the type variables that it does not share with other variables in scope.
+        let is_generalisable = match &value {
+            Expression::Function(_) => true,
+            Expression::Identifier(..) => !mutable,
+            _ => false,
+        };
+
+        let r#type = if matches!(pattern, Pattern::Identifier(_)) && is_generalisable {
+            self.generalise(declared_type)?
+        } else {
+            self.bindings.push((declared_type.clone(), pattern.span()));
+            declared_type
+        };
+
+        Self::check_bindings(&[&pattern])?;
+
+        for identifier in pattern.identifiers() {
+            let Some(declaration) = self.declarations.get(identifier) else {
+                continue;
+            };
+
+            if !declaration.outer {
+                return Err(Error::new(
+                    ErrorType::VariableAlreadyDeclared(identifier.name.clone()),
+                    identifier.span,
+                )
+                .with_label(
+                    declaration.span,
+                    format!("`{}` is first declared here", identifier.name),
+                ));
+            }
+
+            let warning = Error::warning(ErrorType::ShadowedVariable(identifier.name.clone()), identifier.span)
+                .with_label(
+                    declaration.span,
+                    format!("`{}` is first declared here", identifier.name),
+                );
+
+            self.warnings.push(warning);
+        }
+
+        self.declare_pattern(&pattern, r#type.clone())?;
+        self.let_bindings.extend(pattern.identifiers().into_iter().cloned());
+
+        for identifier in pattern.identifiers() {
+            if let Some(declaration) = self.declarations.get_mut(identifier) {
+                declaration.kind = DeclarationKind::Let;
+                declaration.mutable = mutable;
+            }
+        }
+
+        Ok(Statement::Let {
+            pattern,
+            value,
+            r#type,
+            type_span,
+            mutable,
+            span,
+        })
+    }
+
+    fn visit_return_statement(&mut self, value: Option<Expression>, span: Span) -> Result<Statement, Error> {
+        let mut value = value.map(|value| self.visit_expression(value)).transpose()?;
+
+        // A top-level return statement can return anything, as it is treated as the process' exit code.
+        if let Some(return_type) = self.return_type.clone() {
+            value = match value {
+                Some(value) => Some(self.cast(&return_type, value)?),
+                None => self.coerce(&return_type, &Type::Void, span).map(|_| None)?,
+            };
+
+            // A call whose value is returned directly is the last thing its function does.
+            if let Some(Expression::FunctionCall { tail_call, .. }) = &mut value {
+                *tail_call = true;
+            }
+        }
+
+        Ok(Statement::Return { value, span })
+    }
+
+    fn visit_if_statement(
+        &mut self,
+        condition: Expression,
+        then_branch: Vec<Statement>,
+        else_branch: Option<Vec<Statement>>,
+        span: Span,
+    ) -> Result<Statement, Error> {
+        let condition = self.visit_expression(condition)?;
+        self.unify(&Type::Boolean, &condition.r#type(), condition.span())?;
+
+        let (then_narrowing, else_narrowing) = self.narrowing(&condition);
+
+        let then_branch = self.visit_branch(then_branch, then_narrowing);
+        let else_branch = else_branch.map(|it| self.visit_branch(it, else_narrowing.clone()));
+
+        // If the first branch always returns, the rest of the block is only run if the condition was false.
+        if let (None, Some((identifier, r#type))) = (&else_branch, else_narrowing) {
+            if always_returns(&then_branch) {
+                self.narrow(identifier, r#type);
+            }
+        }
+
+        Ok(Statement::If {
+            condition,
+            then_branch,
+            else_branch,
+            span,
+        })
+    }
+
+    /// Resolves a branch of an if statement, where a variable may have been narrowed to a more specific type.
+    /// Variables declared within the branch are not visible outside of it.
+    fn visit_branch(&mut self, statements: Vec<Statement>, narrowing: Narrowing) -> Vec<Statement> {
+        let outer_variables = self.variables.clone();
+        let outer_declarations = self.declarations.clone();
+        let outer_constants = self.constants.clone();
+
+        for declaration in self.declarations.values_mut() {
+            declaration.outer = true;
+        }
+
+        if let Some((identifier, r#type)) = narrowing {
+            self.narrow(identifier, r#type);
+        }
+
+        let statements = self.visit_block(statements);
+
+        // A variable assigned to within the branch may not have the type it was narrowed to once the branch is done.
+        let widened = self
+            .declarations
+            .iter()
+            .filter(|(identifier, declaration)| {
+                outer_declarations.get(identifier).is_some_and(|outer| {
+                    outer.span == declaration.span && outer.unnarrowed.is_some() && declaration.unnarrowed.is_none()
+                })
+            })
+            .map(|(identifier, _)| identifier.clone())
+            .collect::<Vec<_>>();
+
+        self.variables = outer_variables;
+        self.declarations = outer_declarations;
+        self.constants = outer_constants;
+
+        for identifier in &widened {
+            self.widen(identifier);
+        }
+
+        statements
+    }
+
+    /// Narrows a variable to a more specific [type], which only changes the type of its value until it is assigned to.
+    fn narrow(&mut self, identifier: Identifier, r#type: Type) {
+        let declared_type = self.variables.insert(identifier.clone(), r#type);
+
+        if let (Some(declaration), Some(declared_type)) = (self.declarations.get_mut(&identifier), declared_type) {
+            declaration.unnarrowed.get_or_insert(declared_type);
+        }
+    }
+
+    /// Restores the type that a narrowed variable was declared with.
+    fn widen(&mut self, identifier: &Identifier) {
+        if let Some(r#type) = self.declarations.get_mut(identifier).and_then(|it| it.unnarrowed.take()) {
+            self.variables.insert(identifier.clone(), r#type);
+        }
+    }
+
+    /// Returns the types that a [condition] narrows a variable to in the branch where it is true,
+    /// and in the branch where it is false.
+    /// Only checking the type of a variable whose type is a union or `Any` narrows it.
+    fn narrowing(&self, condition: &Expression) -> (Narrowing, Narrowing) {
+        let Expression::Is { value, target, .. } = condition else {
+            return (None, None);
+        };
+
+        let Expression::Identifier(_, identifier) = value.as_ref() else {
+            return (None, None);
+        };
+
+        let remaining = match self.variables.get(identifier).map(|it| self.substitution.apply(it)) {
+            Some(Type::Union(members)) => Type::union(members.into_iter().filter(|it| it != target).collect()),
+
+            // A value of type `Any` which is not of the target type could still be of any other type.
+            Some(Type::Any) => Type::Any,
+
+            _ => return (None, None),
+        };
+
+        (
+            Some((identifier.clone(), target.clone())),
+            Some((identifier.clone(), remaining)),
+        )
+    }
+
+    /// Resolves the types in a function's signature and body.
+    /// If the function is named, it is declared in the current scope before its body is resolved, allowing it to call itself.
+    fn visit_function(&mut self, function: Function) -> Result<Function, Error> {
+        // The function's type parameters are in scope for its signature and its body.
+        let type_parameters = function.type_parameters.clone();
+        self.with_type_parameters(&type_parameters, |resolver| {
+            resolver.visit_function_with_type_parameters(function)
+        })
+    }
+
+    fn visit_function_with_type_parameters(&mut self, function: Function) -> Result<Function, Error> {
+        let body = self.next_body.take();
+        let function = self.resolve_signature(function)?;
+
+        // The body of the function can see the variables in scope where it is declared, as well as its own parameters.
+        let outer_variables = self.variables.clone();
+        let outer_declarations = self.declarations.clone();
+        let outer_constants = self.constants.clone();
+        let outer_return_type = self.return_type.replace(function.return_type.clone());
+
+        for declaration in self.declarations.values_mut() {
+            declaration.captured = true;
+            declaration.outer = true;
+        }
+
+        // While the function's own body is being resolved, it can only call itself with the same types.
+        if let Some(identifier) = &function.identifier {
+            let parameter_types = function.parameters.iter().map(|it| it.r#type.clone()).collect();
+            let r#type = Type::Function(parameter_types, Box::new(function.return_type.clone()));
+
+            self.declare_function(identifier.clone(), r#type, function.parameters.clone());
+        }
+
+        self.bodies.push(body);
+
+        let patterns = function.parameters.iter().map(|it| &it.pattern).collect::<Vec<_>>();
+        let body = Self::check_bindings(&patterns)
+            .and_then(|_| {
+                function
+                    .parameters
+                    .iter()
+                    .try_for_each(|parameter| self.declare_pattern(&parameter.pattern, parameter.r#type.clone()))
+            })
+            .map(|_| self.visit_block(function.body));
+
+        self.bodies.pop();
+        self.variables = outer_variables;
+        self.declarations = outer_declarations;
+        self.constants = outer_constants;
+        self.return_type = outer_return_type;
+
+        let function = Function {
+            body: body?,
+            ..function
+        };
+
+        // Any constraints on the function's type parameters must be checked while its bounds are still known.
+        self.solve_constraints()?;
+
+        // A function which does not always return a value returns nothing.
+        if !always_returns(&function.body) && !self.substitution.unify(&function.return_type, &Type::Void) {
+            return Err(Error::new(
+                ErrorType::MissingReturn(function.name().to_string()),
+                function.span,
+            ));
+        }
+
+        Ok(function)
+    }
+
+    /// Resolves the types of a function's parameters and its return type.
+    /// Any types which are not declared are inferred from how they are used.
+    fn resolve_signature(&mut self, function: Function) -> Result<Function, Error> {
+        let mut parameters = Vec::new();
+        let count = function.parameters.len();
+
+        for (index, parameter) in function.parameters.into_iter().enumerate() {
+            let span = parameter.pattern.span();
+            let r#type = self.resolve_type(parameter.r#type, span)?;
+
+            // The arguments passed to a variadic parameter are collected into a list, after those of the other parameters.
+            if parameter.variadic {
+                if index + 1 != count {
+                    return Err(Error::new(ErrorType::VariadicNotLast, span));
+                }
+
+                match self.substitution.apply(&r#type) {
+                    Type::List(_) => {},
+                    Type::Variable(_) => {
+                        let list = Type::List(Box::new(self.substitution.fresh()));
+                        self.unify(&list, &r#type, span)?;
+                    },
+                    r#type => return Err(Error::new(ErrorType::VariadicNotList(r#type), span)),
+                }
+            }
+
+            // A default value is folded into a literal, so that it can be used wherever the function is called.
+            let default = match parameter.default {
+                Some(default) => {
+                    let default = self.visit_expression(default)?;
+                    if !default.is_literal() {
+                        return Err(Error::new(ErrorType::DefaultNotConstant, span));
+                    }
+
+                    self.coerce(&r#type, &default.r#type(), span)?;
+                    Some(default)
+                },
+
+                None => None,
+            };
+
+            parameters.push(Parameter {
+                pattern: parameter.pattern,
+                r#type,
+                default,
+                variadic: parameter.variadic,
+            });
+        }
+
+        let return_type = self.resolve_type(function.return_type, function.span)?;
+
+        Ok(Function {
+            parameters,
+            return_type,
+            ..function
+        })
+    }
+
+    /// Runs [resolve] with the [type_parameters] of a declaration in scope.
+    fn with_type_parameters<T>(
+        &mut self,
+        type_parameters: &[TypeParameter],
+        resolve: impl FnOnce(&mut Self) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let outer_type_parameters = self.type_parameters.len();
+        let outer_assumptions = self.assumptions.len();
+
+        let result = self.declare_type_parameters(type_parameters).and_then(|_| resolve(self));
+
+        self.type_parameters.truncate(outer_type_parameters);
+        self.assumptions.truncate(outer_assumptions);
+
+        result
+    }
+
+    /// Resolves the types of a struct's fields.
+    /// The struct is declared before its fields are resolved, so that they can refer to it.
+    fn visit_struct(&mut self, r#struct: Struct) -> Result<Struct, Error> {
+        self.check_type_name(&r#struct.identifier)?;
+
+        for (index, field) in r#struct.fields.iter().enumerate() {
+            let identifier = &field.identifier;
+            if let Some(first) = r#struct.fields[..index].iter().find(|it| it.identifier.name == identifier.name) {
+                return Err(
+                    Error::new(ErrorType::DuplicateField(identifier.name.clone()), identifier.span).with_label(
+                        first.identifier.span,
+                        format!("`{}` is first declared here", identifier.name),
+                    ),
+                );
+            }
+        }
+
+        self.structs.insert(r#struct.identifier.name.clone(), r#struct.clone());
+
+        // Only the struct's own type parameters are in scope for its fields.
+        let outer_type_parameters = std::mem::take(&mut self.type_parameters);
+        let outer_assumptions = std::mem::take(&mut self.assumptions);
+
+        let fields = self.declare_type_parameters(&r#struct.type_parameters).and_then(|_| {
+            r#struct
+                .fields
+                .into_iter()
+                .map(|field| {
+                    let r#type = self.resolve_declared_type(field.r#type, field.identifier.span)?;
+                    Ok(Field {
+                        identifier: field.identifier,
+                        r#type,
+                    })
+                })
+                .collect::<Result<Vec<_>, Error>>()
+        });
+
+        self.type_parameters = outer_type_parameters;
+        self.assumptions = outer_assumptions;
+
+        let r#struct = Struct {
+            identifier: r#struct.identifier,
+            type_parameters: r#struct.type_parameters,
+            fields: fields?,
+            span: r#struct.span,
+        };
+
+        // A value of a struct which always contains another value of the same struct could never be created.
+        let name = &r#struct.identifier.name;
+        if let Some(field) = r#struct.fields.iter().find(|it| it.r#type.always_contains(name)) {
+            return Err(
+                Error::new(ErrorType::RecursiveStruct(name.clone()), field.identifier.span)
+                    .with_help("wrap it in a list, or in a union with another type, so that it can end"),
+            );
+        }
+
+        self.structs.insert(r#struct.identifier.name.clone(), r#struct.clone());
+        Ok(r#struct)
+    }
+
+    /// Resolves a newtype, which is a struct with a single field, along with a function to create it.
+    fn visit_newtype(&mut self, newtype: Newtype) -> Result<Newtype, Error> {
+        let r#struct = self.visit_struct(newtype.r#struct())?;
+        let constructor = self.visit_function(newtype.constructor())?;
+        self.declare_function(newtype.identifier.clone(), constructor.r#type(), constructor.parameters);
+
+        Ok(Newtype {
+            r#type: r#struct.fields[0].r#type.clone(),
+            identifier: newtype.identifier,
+            span: newtype.span,
+        })
+    }
+
+    /// Ensures that no other type or trait has already been declared with the name of a new type or trait.
+    fn check_type_name(&self, identifier: &Identifier) -> Result<(), Error> {
+        let name = &identifier.name;
+
+        if self.structs.contains_key(name) || self.traits.contains_key(name) || self.aliases.contains_key(name) {
+            return Err(Error::new(
+                ErrorType::TypeAlreadyDeclared(name.clone()),
+                identifier.span,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Resolves the signatures of a trait's methods, and declares each method as a function
+    /// which is generic over the type implementing the trait.
+    fn visit_trait(&mut self, r#trait: Trait) -> Result<Trait, Error> {
+        self.check_type_name(&r#trait.identifier)?;
+        let name = r#trait.identifier.name.clone();
+
+        // Within a trait, `Self` is whichever type implements it.
+        let outer_self_type = self.self_type.replace(Type::Parameter("Self".to_string()));
+        let methods = self.visit_signatures(r#trait.methods);
+        self.self_type = outer_self_type;
+
+        let r#trait = Trait {
+            identifier: r#trait.identifier,
+            methods: methods?,
+            span: r#trait.span,
+        };
+
+        for method in &r#trait.methods {
+            let r#type = Self::trait_method_type(&name, method);
+            self.declare_variable(method.identifier.clone(), r#type, DeclarationKind::Function);
+        }
+
+        self.traits.insert(name, r#trait.clone());
+        Ok(r#trait)
+    }
+
+    /// Returns the type of a trait's method, which is generic over the type implementing the trait.
+    fn trait_method_type(r#trait: &str, method: &Signature) -> Type {
+        let parameter = GenericParameter::new("Self".to_string(), vec![r#trait.to_string()]);
+        Type::Generic(vec![parameter], Box::new(method.r#type()))
+    }
+
+    /// Resolves the types of the methods declared by a trait.
+    fn visit_signatures(&mut self, methods: Vec<Signature>) -> Result<Vec<Signature>, Error> {
+        let mut signatures: Vec<Signature> = Vec::new();
+
+        for mut method in methods {
+            if signatures.iter().any(|it| it.identifier == method.identifier) {
+                return Err(Error::new(
+                    ErrorType::DuplicateMethod(method.identifier.name),
+                    method.identifier.span,
+                ));
+            }
+
+            self.declare_self_parameter(&method.identifier, &mut method.parameters)?;
+
+            // There is no body to infer the types of the other parameters from, so they must be declared.
+            let mut parameters = Vec::new();
+            for parameter in method.parameters {
+                if parameter.r#type == Type::Unresolved {
+                    return Err(Error::new(ErrorType::UnableToInferType, parameter.pattern.span()));
+                }
+
+                if parameter.default.is_some() {
+                    return Err(Error::new(ErrorType::DefaultNotAllowed, parameter.pattern.span()));
+                }
+
+                if parameter.variadic {
+                    return Err(Error::new(ErrorType::VariadicNotAllowed, parameter.pattern.span()));
+                }
+
+                parameters.push(Parameter {
+                    r#type: self.resolve_declared_type(parameter.r#type, parameter.pattern.span())?,
+                    pattern: parameter.pattern,
+                    default: None,
+                    variadic: false,
+                });
+            }
+
+            signatures.push(Signature {
+                return_type: self.resolve_declared_type(method.return_type, method.identifier.span)?,
+                identifier: method.identifier,
+                parameters,
+            });
+        }
+
+        Ok(signatures)
+    }
+
+    /// Resolves an implementation, which is either of a trait or of a struct's own methods.
+    fn visit_implementation(&mut self, implementation: Implementation) -> Result<Implementation, Error> {
+        // Methods are called on values rather than by name, so their arguments can only be passed by position.
+        for parameter in implementation.methods.iter().flat_map(|it| &it.parameters) {
+            if parameter.default.is_some() {
+                return Err(Error::new(ErrorType::DefaultNotAllowed, parameter.pattern.span()));
+            }
+
+            if parameter.variadic {
+                return Err(Error::new(ErrorType::VariadicNotAllowed, parameter.pattern.span()));
+            }
+        }
+
+        let r#trait = match &implementation.r#trait {
+            Some(identifier) => Some(self.traits.get(&identifier.name).cloned().ok_or(Error::new(
+                ErrorType::UnknownTrait(identifier.name.clone()),
+                identifier.span,
+            ))?),
+
+            None => None,
+        };
+
+        // The implementation's type parameters are in scope for the type it is for, and for its methods.
+        let type_parameters = implementation.type_parameters.clone();
+        self.with_type_parameters(&type_parameters, |resolver| match r#trait {
+            Some(r#trait) => resolver.visit_trait_implementation(implementation, &r#trait),
+            None => resolver.visit_inherent_implementation(implementation),
+        })
+    }
+
+    /// Resolves an implementation of a trait, ensuring that it implements each of the trait's methods.
+    fn visit_trait_implementation(
+        &mut self,
+        implementation: Implementation,
+        r#trait: &Trait,
+    ) -> Result<Implementation, Error> {
+        let name = r#trait.identifier.name.clone();
+        let r#type = self.resolve_declared_type(implementation.r#type, implementation.span)?;
+
+        let Some(head) = r#type.head() else {
+            return Err(Error::new(
+                ErrorType::CannotImplement(name, r#type),
+                implementation.span,
+            ));
+        };
+
+        if self.implementations.contains_key(&(name.clone(), head.clone())) {
+            return Err(Error::new(
+                ErrorType::ImplementationAlreadyDeclared(name, r#type),
+                implementation.span,
+            ));
+        }
+
+        // The implementation is declared before its methods are resolved, so that they can rely on it.
+        let implemented_type = if implementation.type_parameters.is_empty() {
+            r#type.clone()
+        } else {
+            Type::Generic(
+                implementation.type_parameters.iter().map(TypeParameter::parameter).collect(),
+                Box::new(r#type.clone()),
+            )
+        };
+
+        self.implementations.insert((name, head), implemented_type);
+
+        // Within an implementation, `Self` is the type that the trait is implemented for.
+        let outer_self_type = self.self_type.replace(r#type.clone());
+        let methods = self.visit_methods(r#trait, implementation.methods, &r#type, implementation.span);
+        self.self_type = outer_self_type;
+
+        Ok(Implementation {
+            methods: methods?,
+            r#type,
+            ..implementation
+        })
+    }
+
+    /// Resolves the methods of an implementation, which must match the signatures of the [trait]'s methods
+    /// with `Self` replaced by the implementing [type].
+    fn visit_methods(
+        &mut self,
+        r#trait: &Trait,
+        methods: Vec<Function>,
+        r#type: &Type,
+        span: Span,
+    ) -> Result<Vec<Function>, Error> {
+        let substitution = HashMap::from([("Self".to_string(), r#type.clone())]);
+        let mut functions: Vec<Function> = Vec::new();
+
+        for mut method in methods {
+            // Within a method, its name refers to the trait's method rather than to itself,
+            // so that calling it dispatches on the type of its arguments.
+            let identifier = method.identifier.take().expect("methods should always be named");
+
+            let Some(signature) = r#trait.methods.iter().find(|it| it.identifier == identifier) else {
+                return Err(Error::new(
+                    ErrorType::UnknownMethod(r#trait.identifier.name.clone(), identifier.name),
+                    identifier.span,
+                ));
+            };
+
+            if functions.iter().any(|it| it.identifier.as_ref() == Some(&identifier)) {
+                return Err(Error::new(ErrorType::DuplicateMethod(identifier.name), identifier.span));
+            }
+
+            self.declare_self_parameter(&identifier, &mut method.parameters)?;
+            self.implement_method(&identifier);
+
+            // Calling the trait's method by name can run this implementation of it.
+            let uses = self.function_uses.entry(signature.identifier.span).or_default();
+            uses.push((identifier.clone(), identifier.span));
+
+            let function = self.visit_function(method)?;
+
+            self.unify(
+                &signature.r#type().substitute(&substitution),
+                &function.r#type(),
+                identifier.span,
+            )?;
+
+            functions.push(Function {
+                identifier: Some(identifier),
+                ..function
+            });
+        }
+
+        for signature in &r#trait.methods {
+            if !functions.iter().any(|it| it.identifier.as_ref() == Some(&signature.identifier)) {
+                return Err(Error::new(
+                    ErrorType::MissingMethod(r#trait.identifier.name.clone(), signature.identifier.name.clone()),
+                    span,
+                ));
+            }
+        }
+
+        Ok(functions)
+    }
+
+    /// Resolves an implementation of a struct's own methods.
+    fn visit_inherent_implementation(&mut self, implementation: Implementation) -> Result<Implementation, Error> {
+        let r#type = self.resolve_declared_type(implementation.r#type, implementation.span)?;
+
+        let Type::Struct(name, _) = &r#type else {
+            return Err(Error::new(ErrorType::CannotDeclareMethods(r#type), implementation.span));
+        };
+
+        let name = name.clone();
+
+        let outer_self_type = self.self_type.replace(r#type.clone());
+        let methods = self.visit_inherent_methods(&name, implementation.methods, &implementation.type_parameters);
+        self.self_type = outer_self_type;
+
+        Ok(Implementation {
+            methods: methods?,
+            r#type,
+            ..implementation
+        })
+    }
+
+    /// Resolves the methods that the struct called [name] declares itself.
+    /// Every method is declared before any of their bodies are resolved, so that they can call each other.
+    fn visit_inherent_methods(
+        &mut self,
+        name: &str,
+        methods: Vec<Function>,
+        type_parameters: &[TypeParameter],
+    ) -> Result<Vec<Function>, Error> {
+        let mut declared = Vec::new();
+
+        for mut method in methods {
+            // Like the methods of a trait, a struct's methods are only called on values, so are not in scope as variables.
+            let identifier = method.identifier.take().expect("methods should always be named");
+            let key = (name.to_string(), identifier.name.clone());
+
+            if self.methods.contains_key(&key) {
+                return Err(Error::new(ErrorType::DuplicateMethod(identifier.name), identifier.span));
+            }
+
+            self.declare_self_parameter(&identifier, &mut method.parameters)?;
+
+            let method_type_parameters = method.type_parameters.clone();
+            let method =
+                self.with_type_parameters(&method_type_parameters, |resolver| resolver.resolve_signature(method))?;
+
+            self.methods.insert(key, method.r#type());
+            declared.push((identifier, method));
+        }
+
+        let mut functions = Vec::new();
+        for (identifier, method) in declared {
+            self.implement_method(&identifier);
+            let function = self.visit_function(method)?;
+
+            functions.push(Function {
+                identifier: Some(identifier),
+                ..function
+            });
+        }
+
+        // Outside of the implementation, the methods are also generic over its type parameters.
+        for function in &functions {
+            let mut parameters = type_parameters.iter().map(TypeParameter::parameter).collect::<Vec<_>>();
+            let r#type = match function.r#type() {
+                Type::Generic(own_parameters, r#type) => {
+                    parameters.extend(own_parameters);
+                    *r#type
+                },
+
+                r#type => r#type,
+            };
+
+            let r#type = self.generalise(Type::Generic(parameters, Box::new(r#type)))?;
+            self.methods.insert((name.to_string(), function.name().to_string()), r#type);
+        }
+
+        Ok(functions)
+    }
+
+    /// Resolves a call of a method on a value, where the value is passed as the method's first argument.
+    fn visit_method_call(
+        &mut self,
+        value: Expression,
+        method: Identifier,
+        arguments: Vec<Expression>,
+        span: Span,
+    ) -> Result<Expression, Error> {
+        let value = self.visit_expression(value)?;
+
+        let mut expressions = Vec::new();
+        for argument in arguments {
+            expressions.push(self.visit_expression(argument)?);
+        }
+
+        let receiver_type = self.substitution.apply(&value.r#type());
+
+        // Nothing is known about the methods of a value that failed to resolve.
+        if receiver_type == Type::Error {
+            return Ok(Expression::MethodCall {
+                value: Box::new(value),
+                method,
+                arguments: expressions,
+                r#trait: None,
+                span,
+                r#type: Type::Error,
+            });
+        }
+
+        let (method_type, r#trait, is_mutating) = self.method_type(&receiver_type, &method)?;
+
+        for (declaration, depth) in self.implemented_methods.get(&method.name).cloned().unwrap_or_default() {
+            self.use_function(&method, declaration, depth)?;
+        }
+
+        if is_mutating && !value.is_place() {
+            return Err(Error::new(ErrorType::NotAssignable, span));
+        }
+
+        if is_mutating {
+            self.check_mutable(&value)?;
+        }
+
+        let Type::Function(parameters, return_type) = self.instantiate(method_type, &method.name, method.span)? else {
+            unreachable!("methods should always have a function type");
+        };
+
+        let Some((receiver, parameters)) = parameters.split_first() else {
+            unreachable!("methods should always take the value they are called on");
+        };
+
+        self.unify(receiver, &receiver_type, span)?;
+        let expressions = self.check_arguments(parameters, expressions, span)?;
+
+        Ok(Expression::MethodCall {
+            value: Box::new(value),
+            method,
+            arguments: expressions,
+            r#trait,
+            span,
+            r#type: *return_type,
+        })
+    }
+
+    /// Finds the [method] of a value of the given [type].
+    /// The struct's own methods are preferred, followed by the methods of the built-in types, and then trait methods.
+    /// Returns the type of the method, the trait that declares it if it is a trait method, and whether it modifies the value.
+    fn method_type(&self, r#type: &Type, method: &Identifier) -> Result<(Type, Option<String>, bool), Error> {
+        // Methods can only be called on values whose type is already known.
+        if let Type::Variable(_) = r#type {
+            return Err(Error::new(ErrorType::UnableToInferType, method.span));
+        }
+
+        if let Some(head) = r#type.head() {
+            if let Some(method_type) = self.methods.get(&(head.clone(), method.name.clone())) {
+                return Ok((method_type.clone(), None, false));
+            }
+
+            if let Some(builtin) = self.builtin_methods.get(&head, &method.name) {
+                return Ok((builtin.r#type(), None, builtin.is_mutating()));
+            }
+        }
+
+        let traits = self
+            .traits
+            .values()
+            .filter(|it| it.methods.iter().any(|signature| signature.identifier == *method))
+            .filter(|it| self.implements(r#type, &it.identifier.name))
+            .collect::<Vec<_>>();
+
+        match traits.as_slice() {
+            [r#trait] => {
+                let signature = r#trait
+                    .methods
+                    .iter()
+                    .find(|it| it.identifier == *method)
+                    .expect("the trait should declare the method");
+
+                let method_type = Self::trait_method_type(&r#trait.identifier.name, signature);
+                Ok((method_type, Some(r#trait.identifier.name.clone()), false))
+            },
+
+            // The members of a union may each have the method, but it can only be called once the union has been narrowed.
+            [] if matches!(r#type, Type::Union(_)) => {
+                Err(Error::new(ErrorType::UnnarrowedUnion(r#type.clone()), method.span))
+            },
+
+            [] => Err(Error::new(
+                ErrorType::NoMethod(r#type.clone(), method.name.clone()),
+                method.span,
+            )),
+
+            _ => Err(Error::new(
+                ErrorType::AmbiguousMethod(r#type.clone(), method.name.clone()),
+                method.span,
+            )),
+        }
+    }
+
+    /// Whether the [type] implements the [trait], without checking the requirements of a generic implementation.
+    fn implements(&self, r#type: &Type, r#trait: &str) -> bool {
+        match r#type {
+            Type::Parameter(_) => self.assumptions.contains(&Constraint::new(r#type.clone(), r#trait.to_string())),
+            Type::Tuple(_) if r#trait == "Printable" => true,
+            Type::Union(members) => members.iter().all(|it| self.implements(it, r#trait)),
+            _ => r#type.head().is_some_and(|head| self.implementations.contains_key(&(r#trait.to_string(), head))),
+        }
+    }
+
+    /// Declares a variable of the given [type], which shadows any constant with the same name.
+    /// The variable cannot be modified, unless it was declared with `let mut`.
+    fn declare_variable(&mut self, identifier: Identifier, r#type: Type, kind: DeclarationKind) {
+        let declaration = Declaration {
+            span: identifier.span,
+            kind,
+            depth: self.bodies.len(),
+            unnarrowed: None,
+            mutable: false,
+            captured: false,
+            outer: false,
+            hoisted: false,
+            parameters: None,
+        };
+
+        self.constants.remove(&identifier);
+        self.declarations.insert(identifier.clone(), declaration);
+        self.variables.insert(identifier, r#type);
+    }
+
+    /// Declares a variable for a function declaration, whose [parameters] allow it to be called with named arguments.
+    fn declare_function(&mut self, identifier: Identifier, r#type: Type, parameters: Vec<Parameter>) {
+        self.declare_variable(identifier.clone(), r#type, DeclarationKind::Function);
+
+        if let Some(declaration) = self.declarations.get_mut(&identifier) {
+            declaration.parameters = Some(parameters);
+        }
+    }
+
+    /// Matches the [arguments] of a call to the [parameters] of the function being called,
+    /// returning the value of each parameter in order, followed by the arguments left over for a variadic parameter.
+    /// A parameter which is not given an argument takes its default value.
+    /// Every argument that cannot be matched, and every parameter that is missing an argument, is reported.
+    fn match_arguments(
+        &mut self,
+        parameters: &[Parameter],
+        arguments: Vec<Argument>,
+        span: Span,
+    ) -> Result<(Vec<Expression>, Vec<Expression>), Error> {
+        let fixed = parameters.iter().filter(|it| !it.variadic).count();
+        let variadic = fixed < parameters.len();
+
+        let positional = arguments.iter().take_while(|it| it.name.is_none()).count();
+        if positional > fixed && !variadic {
+            return Err(Error::new(
+                ErrorType::InvalidNumberOfArguments(fixed, arguments.len()),
+                span,
+            ));
+        }
+
+        let mut values: Vec<Option<Expression>> = vec![None; fixed];
+        let mut arguments = arguments.into_iter();
+
+        let mut rest = arguments.by_ref().take(positional).map(|it| it.value).collect::<Vec<_>>();
+        for (value, argument) in values.iter_mut().zip(rest.drain(..positional.min(fixed))) {
+            *value = Some(argument);
+        }
+
+        let mut errors = vec![];
+        for argument in arguments {
+            // Once an argument has been passed by name, it would be unclear which parameter the next one is for.
+            let Some(name) = argument.name else {
+                errors.push(Error::new(ErrorType::PositionalAfterNamed, argument.value.span()));
+                continue;
+            };
+
+            let Some(parameter) =
+                parameters.iter().position(|it| matches!(&it.pattern, Pattern::Identifier(it) if *it == name))
+            else {
+                let names = parameters.iter().map(|it| format!("`{}`", it.pattern)).collect::<Vec<_>>();
+                errors.push(
+                    Error::new(ErrorType::UnknownArgument(name.name), name.span)
+                        .with_note(format!("the parameters of the function are {}", names.join(", "))),
+                );
+                continue;
+            };
+
+            if parameters[parameter].variadic {
+                errors.push(Error::new(ErrorType::VariadicArgumentByName(name.name), name.span));
+            } else if values[parameter].is_some() {
+                errors.push(Error::new(ErrorType::DuplicateArgument(name.name), name.span));
+            } else {
+                values[parameter] = Some(argument.value);
+            }
+        }
+
+        let mut matched = vec![];
+        for (value, parameter) in values.into_iter().zip(parameters) {
+            match value.or_else(|| parameter.default.clone()) {
+                Some(value) => matched.push(value),
+                None => errors.push(Error::new(
+                    ErrorType::MissingArgument(parameter.pattern.to_string()),
+                    span,
+                )),
+            }
+        }
+
+        self.fail_with(errors)?;
+        Ok((matched, rest))
+    }
+
+    /// Fails if there are any [errors]. Only one of them can be returned, to be recorded by the statement that failed,
+    /// so the others are recorded before it is, in the order they were found.
+    fn fail_with(&mut self, mut errors: Vec<Error>) -> Result<(), Error> {
+        let Some(last) = errors.pop() else {
+            return Ok(());
+        };
+
+        self.errors.extend(errors);
+        Err(last)
+    }
+
+    /// Collects the [arguments] passed to a variadic parameter into a list, whose elements must all have the same type.
+    fn variadic_list(&mut self, arguments: Vec<Expression>, span: Span) -> Result<Expression, Error> {
+        let element_type = self.substitution.fresh();
+        for argument in &arguments {
+            self.unify(&element_type, &argument.r#type(), argument.span())?;
+        }
+
+        Ok(Expression::List {
+            elements: arguments,
+            span,
+            r#type: Type::List(Box::new(element_type)),
+        })
+    }
+
+    /// Returns the values of the [arguments] of a call to a function which is not known by name,
+    /// whose arguments can only be passed by position.
+    fn positional_arguments(arguments: Vec<Argument>) -> Result<Vec<Expression>, Error> {
+        arguments
+            .into_iter()
+            .map(|argument| match argument.name {
+                Some(name) => Err(Error::new(ErrorType::NamedArgumentsNotAllowed, name.span)),
+                None => Ok(argument.value),
+            })
+            .collect()
+    }
+
+    /// Ensures that the variable that a [place] belongs to can be modified.
+    /// A field or element can only be modified through a variable declared with `let mut`, which makes values deeply immutable.
+    fn check_mutable(&self, place: &Expression) -> Result<(), Error> {
+        let identifier = match place {
+            Expression::Identifier(_, identifier) => identifier,
+
+            Expression::FieldAccess { value, .. }
+            | Expression::TupleAccess { value, .. }
+            | Expression::Index { value, .. } => {
+                return self.check_mutable(value);
+            },
+
+            _ => unreachable!("only places can be modified"),
+        };
+
+        // An undeclared variable is reported when the place is resolved.
+        let Some(declaration) = self.declarations.get(identifier) else {
+            return Ok(());
+        };
+
+        if declaration.captured {
+            Err(
+                Error::new(ErrorType::CapturedVariable(identifier.name.clone()), identifier.span)
+                    .with_label(declaration.span, format!("`{}` is declared here", identifier.name))
+                    .with_note(
+                        "functions capture the variables they use when they are created, so changes would be lost",
+                    ),
+            )
+        } else if !declaration.mutable {
+            let name = &identifier.name;
+            let error = Error::new(ErrorType::ImmutableVariable(name.clone()), identifier.span)
+                .with_label(declaration.span, format!("`{}` is declared here", name));
+
+            Err(match declaration.kind {
+                DeclarationKind::Let => {
+                    error.with_help(format!("declare it with `let mut {}` to allow it to be modified", name))
+                },
+                DeclarationKind::Constant => error.with_help(format!(
+                    "declare it with `let mut {}` instead of `const` to allow it to be modified",
+                    name
+                )),
+                DeclarationKind::Function => error.with_note("functions can never be modified"),
+                DeclarationKind::Binding => error.with_help(format!(
+                    "copy it into a variable with `let mut {} = {}` to modify the copy",
+                    name, name
+                )),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Ensures that the [patterns] bound at the same time, such as a function's parameters, bind each variable only once.
+    fn check_bindings(patterns: &[&Pattern]) -> Result<(), Error> {
+        let identifiers = patterns.iter().flat_map(|it| it.identifiers()).collect::<Vec<_>>();
+
+        for (index, identifier) in identifiers.iter().enumerate() {
+            if identifiers[..index].contains(identifier) {
+                return Err(Error::new(
+                    ErrorType::VariableAlreadyDeclared(identifier.name.clone()),
+                    identifier.span,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Declares the variables bound by a [pattern] that is matched against a value of the given [type].
+    fn declare_pattern(&mut self, pattern: &Pattern, r#type: Type) -> Result<(), Error> {
+        match pattern {
+            Pattern::Identifier(identifier) => {
+                self.declare_variable(identifier.clone(), r#type, DeclarationKind::Binding);
+                Ok(())
+            },
+
+            Pattern::Wildcard(_) => Ok(()),
+
+            Pattern::Tuple(patterns, span) => {
+                let element_types = match self.substitution.apply(&r#type) {
+                    Type::Tuple(elements) if elements.len() == patterns.len() => elements,
+                    Type::Error => patterns.iter().map(|_| Type::Error).collect(),
+
+                    // If the type of the value is not known yet, it must be a tuple with an element for each pattern.
+                    r#type @ Type::Variable(_) => {
+                        let elements = patterns.iter().map(|_| self.substitution.fresh()).collect::<Vec<_>>();
+                        self.unify(&Type::Tuple(elements.clone()), &r#type, *span)?;
+
+                        elements
+                    },
+
+                    r#type => return Err(Error::new(ErrorType::CannotDestructure(r#type, patterns.len()), *span)),
+                };
+
+                for (pattern, r#type) in patterns.iter().zip(element_types) {
+                    self.declare_pattern(pattern, r#type)?;
+                }
+
+                Ok(())
+            },
+        }
+    }
+
+    /// Ensures that the first parameter of a [method] is `self`.
+    /// Unless it is declared otherwise, its type is `Self`.
+    fn declare_self_parameter(&self, method: &Identifier, parameters: &mut [Parameter]) -> Result<(), Error> {
+        match parameters.first_mut() {
+            Some(parameter) if matches!(&parameter.pattern, Pattern::Identifier(identifier) if identifier.name == "self") =>
+            {
+                if parameter.r#type == Type::Unresolved {
+                    parameter.r#type = Type::Named("Self".to_string(), vec![], parameter.pattern.span());
+                }
+
+                Ok(())
+            },
+
+            _ => Err(Error::new(
+                ErrorType::MissingSelfParameter(method.name.clone()),
+                method.span,
+            )),
+        }
+    }
+
+    /// Resolves a struct literal, inferring the struct's type parameters from the values of its fields.
+    fn visit_struct_literal(
+        &mut self,
+        identifier: Identifier,
+        fields: Vec<(Identifier, Expression)>,
+        span: Span,
+    ) -> Result<Expression, Error> {
+        let r#struct = self
+            .structs
+            .get(&identifier.name)
+            .cloned()
+            .ok_or_else(|| self.unknown_type(&identifier.name, identifier.span))?;
+
+        // Each struct literal gets its own instance of the struct's type parameters.
+        let mut arguments = Vec::new();
+        let mut substitution = HashMap::new();
+
+        for parameter in &r#struct.type_parameters {
+            let variable = self.substitution.fresh();
+            self.instantiations.push(Instantiation {
+                variable: variable.clone(),
+                parameter: parameter.identifier.name.clone(),
+                name: identifier.name.clone(),
+                span: identifier.span,
+            });
+
+            substitution.insert(parameter.identifier.name.clone(), variable.clone());
+            arguments.push(variable);
+        }
+
+        for constraint in r#struct.type_parameters.iter().flat_map(|it| it.parameter().constraints()) {
+            self.require(constraint.substitute(&substitution), identifier.span)?;
+        }
+
+        let mut values: HashMap<String, (Identifier, Expression)> = HashMap::new();
+
+        for (field, value) in fields {
+            let Some(declared) = r#struct.fields.iter().find(|it| it.identifier == field) else {
+                return Err(Error::new(
+                    ErrorType::UnknownField(Type::Struct(identifier.name, vec![]), field.name),
+                    field.span,
+                ));
+            };
+
+            let field_type = declared.r#type.substitute(&substitution);
+            let value = self.visit_expected_expression(value, &field_type)?;
+            let value = self.cast(&field_type, value)?;
+
+            if let Some((first, _)) = values.get(&field.name) {
+                return Err(Error::new(ErrorType::DuplicateField(field.name.clone()), field.span)
+                    .with_label(first.span, format!("`{}` is first given here", field.name)));
+            }
+
+            values.insert(field.name.clone(), (field, value));
+        }
+
+        // The fields are stored in the order that they were declared in, regardless of the order they were written in.
+        let mut ordered_fields = Vec::new();
+        for declared in &r#struct.fields {
+            let Some(field) = values.remove(&declared.identifier.name) else {
+                return Err(Error::new(
+                    ErrorType::MissingField(identifier.name, declared.identifier.name.clone()),
+                    span,
+                ));
+            };
+
+            ordered_fields.push(field);
+        }
+
+        Ok(Expression::StructLiteral {
+            r#type: Type::Struct(identifier.name.clone(), arguments),
+            identifier,
+            fields: ordered_fields,
+            span,
+        })
+    }
+
+    /// Returns the type of the [field] of a value of the given [type].
+    fn field_type(&self, r#type: &Type, field: &Identifier) -> Result<Type, Error> {
+        let r#type = self.substitution.apply(r#type);
+
+        let Type::Struct(name, arguments) = &r#type else {
+            // Fields can only be accessed on values whose type is already known.
+            match r#type {
+                Type::Variable(_) => return Err(Error::new(ErrorType::UnableToInferType, field.span)),
+                Type::Error => return Ok(Type::Error),
+                _ => {},
+            }
+
+            return Err(Error::new(
+                ErrorType::UnknownField(r#type, field.name.clone()),
+                field.span,
+            ));
+        };
+
+        let unknown_field = || Error::new(ErrorType::UnknownField(r#type.clone(), field.name.clone()), field.span);
+
+        let r#struct = self.structs.get(name).ok_or_else(unknown_field)?;
+        let declared = r#struct.fields.iter().find(|it| it.identifier == *field).ok_or_else(unknown_field)?;
+
+        let substitution = r#struct
+            .type_parameters
+            .iter()
+            .map(|it| it.identifier.name.clone())
+            .zip(arguments.iter().cloned())
+            .collect();
+
+        Ok(declared.r#type.substitute(&substitution))
+    }
+
+    /// Ensures that the [arguments] passed to a function match the types of its [parameters].
+    fn check_arguments(
+        &mut self,
+        parameters: &[Type],
+        arguments: Vec<Expression>,
+        span: Span,
+    ) -> Result<Vec<Expression>, Error> {
+        // Ensure that the number of arguments matches the number of arguments the function takes.
+        if arguments.len() != parameters.len() {
+            return Err(Error::new(
+                ErrorType::InvalidNumberOfArguments(parameters.len(), arguments.len()),
+                span,
+            ));
+        }
+
+        // Ensure that the types of the arguments match the types of the arguments the function takes.
+        parameters
+            .iter()
+            .zip(arguments)
+            .map(|(expected_type, expression)| self.cast(expected_type, expression))
+            .collect()
+    }
+
+    /// Unifies the [expected] type with the [actual] type, returning an error at [span] if they do not match.
+    fn unify(&mut self, expected: &Type, actual: &Type, span: Span) -> Result<(), Error> {
+        if self.substitution.unify(expected, actual) {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorType::TypeMismatch(self.substitution.apply(expected), self.substitution.apply(actual)),
+                span,
+            ))
+        }
+    }
+
+    /// Resolves an [expression] whose value is expected to be of the [expected] type, e.g. that of a declared variable.
+    /// Each element of a list or tuple literal is resolved against the type expected of it,
+    /// so that the elements of a list can be of different members of a union.
+    fn visit_expected_expression(&mut self, expression: Expression, expected: &Type) -> Result<Expression, Error> {
+        match (expression, self.substitution.apply(expected)) {
+            (Expression::List { elements, span, .. }, Type::List(element_type)) => {
+                let mut expressions = Vec::new();
+                for element in elements {
+                    let element = self.visit_expected_expression(element, &element_type)?;
+                    expressions.push(self.cast(&element_type, element)?);
+                }
+
+                Ok(Expression::List {
+                    elements: expressions,
+                    span,
+                    r#type: Type::List(element_type),
+                })
+            },
+
+            (Expression::Tuple { elements, span, .. }, Type::Tuple(types)) if elements.len() == types.len() => {
+                let mut expressions = Vec::new();
+                for (element, element_type) in elements.into_iter().zip(&types) {
+                    let element = self.visit_expected_expression(element, element_type)?;
+                    expressions.push(self.cast(element_type, element)?);
+                }
+
+                Ok(Expression::Tuple {
+                    elements: expressions,
+                    span,
+                    r#type: Type::Tuple(types),
+                })
+            },
+
+            (expression, _) => self.visit_expression(expression),
+        }
+    }
+
+    /// Ensures that the [value] can be used where the [expected] type is expected.
+    /// A value of type `Any` is cast to the expected type, which is checked when the program runs.
+    /// Any error is reported at the span of the value.
+    fn cast(&mut self, expected: &Type, value: Expression) -> Result<Expression, Error> {
+        let expected = self.substitution.apply(expected);
+        let value = self.cast_literal(&expected, value)?;
+        let span = value.span();
+
+        match (&expected, self.substitution.apply(&value.r#type())) {
+            (Type::Any | Type::Variable(_) | Type::Error, _) | (_, Type::Variable(_) | Type::Error) => {},
+
+            (target, Type::Any) if !target.is_checkable() => {
+                return Err(Error::new(ErrorType::UncheckableType(expected), span));
+            },
+
+            (_, Type::Any) => {
+                return Ok(Expression::Cast {
+                    value: Box::new(value),
+                    target: expected,
+                    span,
+                });
+            },
+
+            _ => {},
+        }
+
+        self.coerce(&expected, &value.r#type(), span)?;
+        Ok(value)
+    }
+
+    /// Casts an [operand] of type `Any` to the type of the [other] operand, so that it is checked when the program runs.
+    /// If the type of the other operand is not known, the operand is cast to an integer, unless the [operator] can also
+    /// be applied to strings.
+    fn cast_operand(
+        &mut self,
+        operator: Operator,
+        operand: Expression,
+        other: &Expression,
+    ) -> Result<Expression, Error> {
+        if self.substitution.apply(&operand.r#type()) != Type::Any {
+            return Ok(operand);
+        }
+
+        match self.substitution.apply(&other.r#type()) {
+            Type::Any | Type::Variable(_) if operator == Operator::Add => Ok(operand),
+            Type::Any | Type::Variable(_) => self.cast(&Type::Integer, operand),
+            r#type => self.cast(&r#type, operand),
+        }
+    }
+
+    /// Casts each element or field of a list, tuple or struct literal to the type [expected] of it,
+    /// so that e.g. `[1]` can be used where a `List<Integer | String>` is expected.
+    /// The literal then has the expected type. Any other value is returned as it is.
+    fn cast_literal(&mut self, expected: &Type, value: Expression) -> Result<Expression, Error> {
+        match (value, expected) {
+            (Expression::List { elements, span, .. }, Type::List(element_type)) => Ok(Expression::List {
+                elements: elements
+                    .into_iter()
+                    .map(|element| self.cast(element_type, element))
+                    .collect::<Result<_, _>>()?,
+                span,
+                r#type: expected.clone(),
+            }),
+
+            (Expression::Tuple { elements, span, .. }, Type::Tuple(types)) if elements.len() == types.len() => {
+                Ok(Expression::Tuple {
+                    elements: elements
+                        .into_iter()
+                        .zip(types)
+                        .map(|(element, element_type)| self.cast(element_type, element))
+                        .collect::<Result<_, _>>()?,
+                    span,
+                    r#type: expected.clone(),
+                })
+            },
+
+            (
+                Expression::StructLiteral {
+                    identifier,
+                    fields,
+                    span,
+                    r#type,
+                },
+                Type::Struct(name, arguments),
+            ) if identifier.name == *name => {
+                let r#struct = self.structs.get(name).cloned().expect("struct literals should be of declared structs");
+                let substitution = r#struct
+                    .type_parameters
+                    .iter()
+                    .map(|it| it.identifier.name.clone())
+                    .zip(arguments.iter().cloned())
+                    .collect::<HashMap<_, _>>();
+
+                // The fields of a resolved struct literal are in the order that they were declared in.
+                let fields = fields
+                    .into_iter()
+                    .zip(&r#struct.fields)
+                    .map(|((field, value), declared)| {
+                        let value = self.cast(&declared.r#type.substitute(&substitution), value)?;
+                        Ok((field, value))
+                    })
+                    .collect::<Result<_, Error>>()?;
+
+                // The literal's own type arguments were inferred from its fields, which may be members of the expected ones.
+                if let Type::Struct(_, inferred) = self.substitution.apply(&r#type) {
+                    for (argument, inferred) in arguments.iter().zip(&inferred) {
+                        self.coerce(argument, inferred, span)?;
+                    }
+                }
+
+                Ok(Expression::StructLiteral {
+                    identifier,
+                    fields,
+                    span,
+                    r#type: expected.clone(),
+                })
+            },
+
+            (value, _) => Ok(value),
+        }
+    }
+
+    /// Ensures that a value of the [actual] type can be used where the [expected] type is expected.
+    /// This is the same as unifying them, except that a value of any member of a union can be used where the union is expected.
+    fn coerce(&mut self, expected: &Type, actual: &Type, span: Span) -> Result<(), Error> {
+        let expected_type = self.substitution.apply(expected);
+        let actual_type = self.substitution.apply(actual);
+
+        match (&expected_type, &actual_type) {
+            (Type::Error, _) | (_, Type::Error) => Ok(()),
+
+            (Type::Union(members), Type::Union(actual_members))
+                if actual_members.iter().all(|it| members.contains(it)) =>
+            {
+                Ok(())
+            },
+
+            (Type::Union(members), _) if members.contains(&actual_type) => Ok(()),
+
+            // A value whose type is not fully inferred yet is a member with the same head, e.g. `[]` is the list member.
+            (Type::Union(members), _)
+                if members.iter().any(|it| it.head().is_some() && it.head() == actual_type.head()) =>
+            {
+                let member = members.iter().find(|it| it.head() == actual_type.head()).cloned();
+                self.unify(&member.expect("a member should have the same head"), &actual_type, span)
+            },
+
+            // Any value can be used where any type of value is expected.
+            (Type::Any, _) => Ok(()),
+
+            // A union can only be used where just one of its members is expected once it has been narrowed.
+            (Type::Variable(_) | Type::Union(_), _) => self.unify(expected, actual, span),
+            (_, Type::Union(_)) => Err(Error::new(ErrorType::UnnarrowedUnion(actual_type), span)),
+
+            _ => self.unify(expected, actual, span),
+        }
+    }
+
+    /// Generalises the type of a function, making it generic over any type variables that it still contains.
+    /// Type variables which also appear in the type of a variable in scope are left alone, as they may still be inferred.
+    /// Any traits that the generalised type variables must implement become constraints of the generic type.
+    fn generalise(&mut self, r#type: Type) -> Result<Type, Error> {
+        self.solve_constraints()?;
+
+        // The type of a function with type parameters is already generic over them.
+        let (mut parameters, r#type) = match r#type {
+            Type::Generic(parameters, r#type) => (parameters, *r#type),
+            r#type => (vec![], r#type),
+        };
+
+        let r#type = self.substitution.apply(&r#type);
+
+        let variables_in_scope =
+            self.variables.values().flat_map(|it| self.substitution.apply(it).variables()).collect::<HashSet<_>>();
+
+        let mut generalised = vec![];
+
+        for variable in r#type.variables() {
+            if variables_in_scope.contains(&variable) {
+                continue;
+            }
+
+            // Generalised type parameters are given names that cannot be written in source code,
+            // so that they never conflict with declared type parameters.
+            let letter = (b'a' + (self.generalised_parameters % 26) as u8) as char;
+            let name = match self.generalised_parameters / 26 {
+                0 => format!("'{}", letter),
+                suffix => format!("'{}{}", letter, suffix),
+            };
+
+            self.generalised_parameters += 1;
+
+            // The variable is bound to the parameter, so that the function's own types refer to it.
+            self.substitution.bind(variable, Type::Parameter(name.clone()));
+            generalised.push(GenericParameter::new(name, vec![]));
+        }
+
+        // Constraints which could not be checked yet, as they are on a generalised type variable,
+        // instead become bounds of its type parameter, which are checked each time the generic type is instantiated.
+        for (constraint, span) in std::mem::take(&mut self.constraints) {
+            let Type::Parameter(name) = self.substitution.apply(&constraint.r#type) else {
+                self.constraints.push((constraint, span));
+                continue;
+            };
+
+            let Some(parameter) = generalised.iter_mut().find(|it| it.name == name) else {
+                self.constraints.push((constraint, span));
+                continue;
+            };
+
+            if !parameter.bounds.contains(&constraint.r#trait) {
+                parameter.bounds.push(constraint.r#trait);
+            }
+        }
+
+        parameters.extend(generalised);
+        let r#type = self.substitution.apply(&r#type);
+
+        if parameters.is_empty() {
+            Ok(r#type)
+        } else {
+            Ok(Type::Generic(parameters, Box::new(r#type)))
+        }
+    }
+
+    /// Instantiates a generic type, replacing each of its type parameters with a new type variable.
+    /// The [name] and [span] are used to report a type parameter which could not be inferred,
+    /// or which does not implement the traits it is required to.
+    fn instantiate(&mut self, r#type: Type, name: &str, span: Span) -> Result<Type, Error> {
+        let Type::Generic(parameters, r#type) = r#type else {
+            return Ok(r#type);
+        };
+
+        let (substitution, constraints) = self.fresh_parameters(&parameters);
+
+        for parameter in parameters {
+            self.instantiations.push(Instantiation {
+                variable: substitution[&parameter.name].clone(),
+                parameter: parameter.name,
+                name: name.to_string(),
+                span,
+            });
+        }
+
+        for constraint in constraints {
+            self.require(constraint, span)?;
+        }
+
+        Ok(r#type.substitute(&substitution))
+    }
+
+    /// Creates a new type variable for each of the type [parameters].
+    /// Returns the substitution of each parameter with its variable, and the traits that the variables must implement.
+    fn fresh_parameters(&mut self, parameters: &[GenericParameter]) -> (HashMap<String, Type>, Vec<Constraint>) {
+        let substitution =
+            parameters.iter().map(|it| (it.name.clone(), self.substitution.fresh())).collect::<HashMap<_, _>>();
+
+        let constraints =
+            parameters.iter().flat_map(GenericParameter::constraints).map(|it| it.substitute(&substitution)).collect();
+
+        (substitution, constraints)
+    }
+
+    /// Requires the type in the [constraint] to implement its trait.
+    /// If the type is not known yet, the constraint is checked once it is.
+    fn require(&mut self, constraint: Constraint, span: Span) -> Result<(), Error> {
+        let r#type = self.substitution.apply(&constraint.r#type);
+        let not_implemented = || match constraint.r#trait.as_str() {
+            ADDABLE => Error::new(ErrorType::UnsupportedOperator(Operator::Add, r#type.clone()), span),
+            r#trait => Error::new(
+                ErrorType::TraitNotImplemented(r#trait.to_string(), r#type.clone()),
+                span,
+            ),
+        };
+
+        match &r#type {
+            Type::Variable(_) => {
+                self.constraints.push((constraint, span));
+                Ok(())
+            },
+
+            // A value of type `Any` is checked to be printable when it is printed.
+            Type::Any if constraint.r#trait == "Printable" => Ok(()),
+
+            // The members of a union could be added to each other, so values of it cannot be added.
+            Type::Union(_) if constraint.r#trait == ADDABLE => Err(not_implemented()),
+
+            // Like a list, a tuple is printable if its elements are.
+            Type::Tuple(elements) if constraint.r#trait == "Printable" => {
+                for element in elements {
+                    self.require(Constraint::new(element.clone(), constraint.r#trait.clone()), span)?;
+                }
+
+                Ok(())
+            },
+
+            // A union implements a trait if each of its members do, as the implementation is chosen at runtime.
+            Type::Union(members) => {
+                for member in members {
+                    self.require(Constraint::new(member.clone(), constraint.r#trait.clone()), span)?;
+                }
+
+                Ok(())
+            },
+
+            // Within a generic declaration, its type parameters only implement the traits they are bounded by.
+            Type::Parameter(_) => {
+                if self.assumptions.contains(&Constraint::new(r#type.clone(), constraint.r#trait.clone())) {
+                    Ok(())
+                } else {
+                    Err(not_implemented())
+                }
+            },
+
+            _ => {
+                let implementation = r#type
+                    .head()
+                    .and_then(|head| self.implementations.get(&(constraint.r#trait.clone(), head)))
+                    .cloned()
+                    .ok_or_else(not_implemented)?;
+
+                // A generic implementation may require its type parameters to implement traits themselves,
+                // e.g. a list is only printable if its elements are.
+                let (implemented_type, constraints) = match implementation {
+                    Type::Generic(parameters, implemented_type) => {
+                        let (substitution, constraints) = self.fresh_parameters(&parameters);
+                        (implemented_type.substitute(&substitution), constraints)
+                    },
+
+                    implemented_type => (implemented_type, vec![]),
+                };
+
+                self.unify(&implemented_type, &r#type, span)?;
+
+                for constraint in constraints {
+                    self.require(constraint, span)?;
+                }
+
+                Ok(())
+            },
+        }
+    }
+
+    /// Checks any constraints whose types were not known when they were required, and may be known now.
+    fn solve_constraints(&mut self) -> Result<(), Error> {
+        for (constraint, span) in std::mem::take(&mut self.constraints) {
+            self.require(constraint, span)?;
+        }
+
+        Ok(())
+    }
+
+    /// Declares the [type_parameters] of a declaration, which must only be bounded by traits that exist.
+    fn declare_type_parameters(&mut self, type_parameters: &[TypeParameter]) -> Result<(), Error> {
+        for (index, type_parameter) in type_parameters.iter().enumerate() {
+            let identifier = &type_parameter.identifier;
+            if let Some(first) = type_parameters[..index].iter().find(|it| it.identifier.name == identifier.name) {
+                return Err(Error::new(
+                    ErrorType::DuplicateTypeParameter(identifier.name.clone()),
+                    identifier.span,
+                )
+                .with_label(
+                    first.identifier.span,
+                    format!("`{}` is first declared here", identifier.name),
+                ));
+            }
+
+            if let Some(bound) = type_parameter.bounds.iter().find(|it| !self.traits.contains_key(&it.name)) {
+                return Err(Error::new(ErrorType::UnknownTrait(bound.name.clone()), bound.span));
+            }
+
+            self.type_parameters.push(type_parameter.identifier.name.clone());
+            self.assumptions.extend(type_parameter.parameter().constraints());
+        }
+
+        Ok(())
+    }
+
+    /// Adds a suggestion to an [error] about an unknown variable or function called [name], if the variables in scope
+    /// or the built-in functions, or the [keywords] if they are included, have a similar name.
+    fn suggest_name(&self, error: Error, name: &str, keywords: bool) -> Error {
+        let variables = self.variables.keys().map(|it| (it.name.as_str(), "variable"));
+        let functions = BuiltinFunctions::NAMES.into_iter().map(|it| (it, "built-in function"));
+        let keywords = Keyword::ALL.iter().filter(|_| keywords).map(|it| (it.name(), "keyword"));
+
+        match suggestion::suggest(name, variables.chain(functions).chain(keywords)) {
+            Some((candidate, kind)) => {
+                error.with_help(format!("a {} with a similar name exists: `{}`", kind, candidate))
+            },
+            None => error,
+        }
+    }
+
+    /// Returns the error for a type called [name] which has not been declared,
+    /// suggesting a type in scope with a similar name if there is one.
+    fn unknown_type(&self, name: &str, span: Span) -> Error {
+        let error = Error::new(ErrorType::UnableToResolveType(name.to_string()), span);
+
+        let builtin = ["Any", "Integer", "String", "Boolean", "List"];
+        let declared = self.structs.keys().chain(self.aliases.keys()).chain(&self.type_parameters);
+        let self_type = self.self_type.as_ref().map(|_| "Self");
+
+        let candidates = builtin.into_iter().chain(declared.map(String::as_str)).chain(self_type);
+        match suggestion::suggest(name, candidates.map(|it| (it, "type"))) {
+            Some((candidate, _)) => error.with_help(format!("a type with a similar name exists: `{}`", candidate)),
+            None => error,
+        }
+    }
+
+    /// Resolves a type which was written in the source code, where any type that was not written is inferred.
+    fn resolve_type(&mut self, r#type: Type, last_span: Span) -> Result<Type, Error> {
+        match r#type {
+            Type::Unresolved => Ok(self.substitution.fresh()),
+            _ => self.resolve_declared_type(r#type, last_span),
+        }
+    }
+
+    /// Resolves a type which was written in the source code.
+    /// Errors in a type written by name point at the name, and errors in any other type at the [last_span].
+    fn resolve_declared_type(&self, r#type: Type, last_span: Span) -> Result<Type, Error> {
+        match r#type {
+            Type::Named(name, arguments, span) if arguments.is_empty() => {
+                if self.type_parameters.contains(&name) {
+                    return Ok(Type::Parameter(name));
+                }
+
+                if let (Some(self_type), "Self") = (&self.self_type, name.as_str()) {
+                    return Ok(self_type.clone());
+                }
+
+                if let Some(r#type) = self.aliases.get(&name) {
+                    return Ok(r#type.clone());
+                }
+
+                self.resolve_struct_type(name, arguments, span)
+            },
+
+            Type::Named(name, arguments, span) => self.resolve_struct_type(name, arguments, span),
+
+            Type::Function(parameters, return_type) => {
+                let parameters = parameters
+                    .into_iter()
+                    .map(|it| self.resolve_declared_type(it, last_span))
+                    .collect::<Result<_, _>>()?;
+
+                let return_type = self.resolve_declared_type(*return_type, last_span)?;
+                Ok(Type::Function(parameters, Box::new(return_type)))
+            },
+
+            Type::List(element_type) => {
+                let element_type = self.resolve_declared_type(*element_type, last_span)?;
+                Ok(Type::List(Box::new(element_type)))
+            },
+
+            Type::Tuple(elements) => {
+                let elements = elements
+                    .into_iter()
+                    .map(|it| self.resolve_declared_type(it, last_span))
+                    .collect::<Result<_, _>>()?;
+
+                Ok(Type::Tuple(elements))
+            },
+
+            Type::Union(members) => {
+                let members = members
+                    .into_iter()
+                    .map(|it| self.resolve_declared_type(it, last_span))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                // A member which could not be resolved has already been reported.
+                if members.contains(&Type::Error) {
+                    return Ok(Type::Error);
+                }
+
+                // Values are narrowed by checking which member they are at runtime,
+                // so no two members can have the same kind of value (e.g. two different lists).
+                let heads = members.iter().map(Type::head).collect::<Vec<_>>();
+                let is_distinguishable =
+                    heads.iter().enumerate().all(|(index, head)| head.is_some() && !heads[..index].contains(head));
+
+                let union = Type::union(members);
+                if !is_distinguishable {
+                    return Err(Error::new(ErrorType::IndistinguishableUnion(union), last_span));
+                }
+
+                Ok(union)
+            },
+
+            // Otherwise, return the type as is.
+            _ => Ok(r#type),
+        }
+    }
+
+    /// Resolves a struct, or a list, written by [name] at [span] with the given type [arguments].
+    fn resolve_struct_type(&self, name: String, arguments: Vec<Type>, span: Span) -> Result<Type, Error> {
+        let expected = match name.as_str() {
+            "List" => 1,
+            _ => self.structs.get(&name).ok_or_else(|| self.unknown_type(&name, span))?.type_parameters.len(),
+        };
+
+        if arguments.len() != expected {
+            return Err(Error::new(
+                ErrorType::InvalidNumberOfTypeArguments(name, expected, arguments.len()),
+                span,
+            ));
+        }
+
+        let arguments =
+            arguments.into_iter().map(|it| self.resolve_declared_type(it, span)).collect::<Result<_, _>>()?;
+
+        Ok(Type::Struct(name, arguments))
     }
 }
@@ -0,0 +1,171 @@
+use crate::r#type::Type;
+
+/// The types that the resolver's type variables have been inferred to be.
+#[derive(Default)]
+pub struct Substitution {
+    /// The type that each type variable is bound to, indexed by the variable.
+    /// A variable that has not been bound yet has not been inferred yet.
+    bindings: Vec<Option<Type>>,
+}
+
+impl Substitution {
+    /// Creates a new type variable, which is not bound to any type yet.
+    pub fn fresh(&mut self) -> Type {
+        self.bindings.push(None);
+        Type::Variable(self.bindings.len() - 1)
+    }
+
+    /// Binds a type variable to a type.
+    pub fn bind(&mut self, variable: usize, r#type: Type) {
+        self.bindings[variable] = Some(r#type);
+    }
+
+    /// Replaces any bound type variables in the type with the types that they are bound to.
+    pub fn apply(&self, r#type: &Type) -> Type {
+        match r#type {
+            Type::Variable(variable) => match &self.bindings[*variable] {
+                Some(bound) => self.apply(bound),
+                None => r#type.clone(),
+            },
+
+            Type::Function(parameters, return_type) => Type::Function(
+                parameters.iter().map(|it| self.apply(it)).collect(),
+                Box::new(self.apply(return_type)),
+            ),
+
+            Type::List(element_type) => Type::List(Box::new(self.apply(element_type))),
+
+            Type::Tuple(elements) => Type::Tuple(elements.iter().map(|it| self.apply(it)).collect()),
+
+            Type::Union(members) => Type::union(members.iter().map(|it| self.apply(it)).collect()),
+
+            Type::Struct(name, arguments) => {
+                Type::Struct(name.clone(), arguments.iter().map(|it| self.apply(it)).collect())
+            },
+
+            Type::Generic(parameters, r#type) => Type::Generic(parameters.clone(), Box::new(self.apply(r#type))),
+
+            _ => r#type.clone(),
+        }
+    }
+
+    /// Unifies two types, binding any type variables in them so that they become the same type.
+    /// Returns `false` if the types can never be the same, in which case some variables may already have been bound.
+    pub fn unify(&mut self, left: &Type, right: &Type) -> bool {
+        let left = self.resolve_variable(left);
+        let right = self.resolve_variable(right);
+
+        match (&left, &right) {
+            // Variables are left unbound, so that they can still be inferred from the rest of the program.
+            (Type::Error, _) | (_, Type::Error) => true,
+
+            (Type::Variable(left), Type::Variable(right)) if left == right => true,
+
+            (Type::Variable(variable), other) | (other, Type::Variable(variable)) => {
+                // A variable cannot be bound to a type containing itself, as that type would be infinitely large.
+                if self.apply(other).variables().contains(variable) {
+                    return false;
+                }
+
+                self.bind(*variable, other.clone());
+                true
+            },
+
+            (Type::Function(left_parameters, left_return), Type::Function(right_parameters, right_return)) => {
+                left_parameters.len() == right_parameters.len()
+                    && left_parameters.iter().zip(right_parameters).all(|(left, right)| self.unify(left, right))
+                    && self.unify(left_return, right_return)
+            },
+
+            (Type::List(left), Type::List(right)) => self.unify(left, right),
+
+            (Type::Tuple(left), Type::Tuple(right)) => {
+                left.len() == right.len() && left.iter().zip(right).all(|(left, right)| self.unify(left, right))
+            },
+
+            // The members of a union can be written in any order.
+            (Type::Union(left), Type::Union(right)) => {
+                let left = self.apply(&Type::Union(left.clone()));
+                let right = self.apply(&Type::Union(right.clone()));
+
+                match (left, right) {
+                    (Type::Union(left), Type::Union(right)) => self.unify_members(left, right),
+                    (left, right) => self.unify(&left, &right),
+                }
+            },
+
+            (Type::Struct(left_name, left_arguments), Type::Struct(right_name, right_arguments)) => {
+                left_name == right_name
+                    && left_arguments.len() == right_arguments.len()
+                    && left_arguments.iter().zip(right_arguments).all(|(left, right)| self.unify(left, right))
+            },
+
+            _ => left == right,
+        }
+    }
+
+    /// Unifies the members of two unions, which may be in any order.
+    /// Members that are the same in both are matched first, and then members with the same head, e.g. two lists.
+    /// Any members left over in one union are what a type variable left over in the other stands for,
+    /// e.g. `Integer | T` and `Integer | String` are unified by binding `T` to `String`.
+    fn unify_members(&mut self, left: Vec<Type>, right: Vec<Type>) -> bool {
+        let mut left_rest = vec![];
+        let mut right_rest = right;
+
+        for member in left {
+            if let Some(index) = right_rest.iter().position(|it| *it == member) {
+                right_rest.remove(index);
+                continue;
+            }
+
+            let head = member.head();
+            match right_rest.iter().position(|it| head.is_some() && it.head() == head) {
+                Some(index) => {
+                    let other = right_rest.remove(index);
+                    if !self.unify(&member, &other) {
+                        return false;
+                    }
+                },
+
+                None => left_rest.push(member),
+            }
+        }
+
+        let (left_variables, left_rest): (Vec<_>, Vec<_>) =
+            left_rest.into_iter().partition(|it| matches!(it, Type::Variable(_)));
+        let (right_variables, right_rest): (Vec<_>, Vec<_>) =
+            right_rest.into_iter().partition(|it| matches!(it, Type::Variable(_)));
+
+        match (left_variables.as_slice(), right_variables.as_slice()) {
+            ([], []) => left_rest.is_empty() && right_rest.is_empty(),
+
+            ([variable], []) => {
+                left_rest.is_empty() && !right_rest.is_empty() && self.unify(variable, &Type::union(right_rest))
+            },
+
+            ([], [variable]) => {
+                right_rest.is_empty() && !left_rest.is_empty() && self.unify(variable, &Type::union(left_rest))
+            },
+
+            // Which members several variables would stand for is ambiguous, unless there are none left to share out.
+            _ => {
+                left_rest.is_empty()
+                    && right_rest.is_empty()
+                    && left_variables.len() == right_variables.len()
+                    && left_variables.iter().zip(&right_variables).all(|(left, right)| self.unify(left, right))
+            },
+        }
+    }
+
+    /// Follows the bindings of a type variable, until reaching a type that is not a bound variable.
+    fn resolve_variable(&self, r#type: &Type) -> Type {
+        match r#type {
+            Type::Variable(variable) => match &self.bindings[*variable] {
+                Some(bound) => self.resolve_variable(bound),
+                None => r#type.clone(),
+            },
+
+            _ => r#type.clone(),
+        }
+    }
+}
@@ -0,0 +1,46 @@
+/// Returns the candidate whose name is most similar to [name], along with what kind of thing it names,
+/// if it is similar enough that [name] is likely to be a misspelling of it.
+/// Names are compared by their edit distance, which can be at most a third of the length of [name].
+pub fn suggest<'a>(name: &str, candidates: impl IntoIterator<Item = (&'a str, &'a str)>) -> Option<(&'a str, &'a str)> {
+    let limit = (name.chars().count() / 3).max(1);
+
+    candidates
+        .into_iter()
+        .map(|(candidate, kind)| (distance(name, candidate), candidate, kind))
+        .filter(|(distance, candidate, _)| *distance <= limit && *candidate != name)
+        // Ties are broken by the candidate's name, so that the suggestion does not depend on the order of the candidates.
+        .min_by_key(|(distance, candidate, _)| (*distance, *candidate))
+        .map(|(_, candidate, kind)| (candidate, kind))
+}
+
+/// Returns the edit distance between two strings: the number of characters that must be inserted, removed or replaced,
+/// or pairs of adjacent characters that must be swapped, to turn one into the other.
+fn distance(from: &str, to: &str) -> usize {
+    let from = from.chars().collect::<Vec<_>>();
+    let to = to.chars().collect::<Vec<_>>();
+
+    // The distances from the characters of `from` seen so far, and from all but the last of them, to each prefix of `to`.
+    let mut previous = (0..=to.len()).collect::<Vec<_>>();
+    let mut before_previous = previous.clone();
+
+    for (index, from_char) in from.iter().enumerate() {
+        let mut current = vec![index + 1];
+
+        for (to_index, to_char) in to.iter().enumerate() {
+            let replaced = previous[to_index] + usize::from(from_char != to_char);
+            let removed = previous[to_index + 1] + 1;
+            let inserted = current[to_index] + 1;
+            let mut distance = replaced.min(removed).min(inserted);
+
+            if index > 0 && to_index > 0 && from[index - 1] == *to_char && from_char == &to[to_index - 1] {
+                distance = distance.min(before_previous[to_index - 1] + 1);
+            }
+
+            current.push(distance);
+        }
+
+        before_previous = std::mem::replace(&mut previous, current);
+    }
+
+    previous[to.len()]
+}
@@ -0,0 +1,52 @@
+/// A range of the source code, from the byte offset of its first character up to the byte offset after its last.
+/// The line and column of a span are only worked out when they are needed, from the source code itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Copy, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Returns the smallest span that covers both this span and [other], e.g. from the start of an expression to its end.
+    pub fn to(self, other: Span) -> Self {
+        Self::new(self.start.min(other.start), self.end.max(other.end))
+    }
+
+    /// Returns the location of the start of the span in [source].
+    pub fn start_location(&self, source: &str) -> Location {
+        Location::of(self.start, source)
+    }
+
+    /// Returns the location of the end of the span in [source].
+    pub fn end_location(&self, source: &str) -> Location {
+        Location::of(self.end, source)
+    }
+}
+
+/// A line and column of the source code, both counted from zero.
+/// The column is counted in characters rather than bytes, so that it lines up with the source code when printed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Copy)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    /// Returns the location of the byte [offset] in [source].
+    /// An offset past the end of the source code is at the end of its last line.
+    pub fn of(offset: usize, source: &str) -> Self {
+        let offset = offset.min(source.len());
+        let before = source.get(..offset).unwrap_or(source);
+
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+
+        Self {
+            line: before.matches('\n').count(),
+            column: before[line_start..].chars().count(),
+        }
+    }
+}
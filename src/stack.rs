@@ -0,0 +1,32 @@
+//! Keeps track of how much stack space the current thread has left, so that code which is nested too deeply to be
+//! handled is reported as an error, rather than overflowing the stack and aborting the process.
+
+use std::cell::Cell;
+
+/// The stack space kept free once the stack is considered to be exhausted,
+/// which is enough for the work done between two checks and for reporting the error.
+const RESERVED_SIZE: usize = 1024 * 1024;
+
+thread_local! {
+    /// The address below which the current thread's stack is considered to be exhausted, if its size is known.
+    static LIMIT: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Records that the current thread's stack is [size] bytes in total, of which very little has been used so far.
+pub fn set_size(size: usize) {
+    let limit = address().saturating_sub(size.saturating_sub(RESERVED_SIZE));
+    LIMIT.with(|it| it.set(Some(limit)));
+}
+
+/// Whether the current thread has used up its stack, other than the space reserved for reporting it.
+/// The stack is assumed to grow downwards, as it does on every platform that Cecelia runs on.
+pub fn is_exhausted() -> bool {
+    LIMIT.with(Cell::get).is_some_and(|limit| address() < limit)
+}
+
+/// Returns an address near the top of the current thread's stack.
+#[inline(never)]
+fn address() -> usize {
+    let marker = 0u8;
+    std::hint::black_box(&marker) as *const u8 as usize
+}
@@ -1,31 +1,37 @@
 pub struct Stream<T> {
     elements: Vec<T>,
     index: usize,
-
-    /// Used for keeping track of the current position in the input
-    /// This can be modified by the lexer to keep track of the current line and column
-    pub visual_index: usize,
 }
 
 impl<T: Clone> Stream<T> {
     pub fn new(elements: Vec<T>) -> Self {
-        Stream {
-            elements,
-            index: 0,
-            visual_index: 0,
-        }
+        Stream { elements, index: 0 }
     }
 
     pub fn peek(&self) -> Option<T> {
         self.elements.get(self.index).cloned()
     }
 
+    /// Returns the last element that was consumed, if any.
+    pub fn previous(&self) -> Option<T> {
+        self.index.checked_sub(1).and_then(|index| self.elements.get(index)).cloned()
+    }
+
+    /// The number of elements that have been consumed.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the elements consumed since the stream was at the given [start] index.
+    pub fn consumed_since(&self, start: usize) -> &[T] {
+        &self.elements[start.min(self.index)..self.index]
+    }
+
     pub fn consume(&mut self) -> Option<T> {
         let element = self.peek();
 
         if self.index < self.elements.len() {
             self.index += 1;
-            self.visual_index += 1;
         }
 
         element
@@ -34,7 +40,6 @@ impl<T: Clone> Stream<T> {
     pub fn unconsume(&mut self) {
         if self.index > 0 {
             self.index -= 1;
-            self.visual_index -= 1;
         }
     }
 }
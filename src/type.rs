@@ -1,17 +1,343 @@
-#[derive(Debug, Clone, PartialEq, Eq)]
+use std::{collections::HashMap, fmt::Display};
+
+use crate::span::Span;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub enum Type {
-    /// A type that is not yet resolved.
-    /// This can either be a type that is not yet defined (e.g. a custom class, Some(X)), or a type that is not yet inferred (None).
-    Unresolved(Option<String>),
+    /// A type that is not yet inferred, as it was not written in the source code.
+    #[default]
+    Unresolved,
 
+    /// A type written by name in the source code, which the resolver has not looked up yet,
+    /// along with its type arguments and where it was written, e.g. `Point` or `Pair<Integer, String>`.
+    Named(String, Vec<Type>, Span),
+
+    /// A value of any type, which must be cast with `as` before it can be used as a more specific type.
     Any,
+
     Void,
     Integer,
     String,
+    Boolean,
+
+    /// A function, taking arguments of the first types and returning a value of the second type.
+    Function(Vec<Type>, Box<Type>),
+
+    /// A list of values of the same type.
+    List(Box<Type>),
+
+    /// A fixed number of values, each of their own type, e.g. `(Integer, String)`.
+    /// A tuple always has at least two elements.
+    Tuple(Vec<Type>),
+
+    /// A value of any one of the member types, e.g. `Integer | String`.
+    /// A union always has at least two members, which can be told apart at runtime.
+    Union(Vec<Type>),
+
+    /// An instance of a struct, along with the types its type parameters were instantiated with.
+    Struct(String, Vec<Type>),
+
+    /// A type parameter, e.g. the `T` in `fn first<T>(xs: List<T>): T`.
+    Parameter(String),
+
+    /// A type which is generic over the type parameters, e.g. the type of a generic function.
+    /// A new instance of the inner type is created each time the value is used.
+    Generic(Vec<GenericParameter>, Box<Type>),
+
+    /// A type variable, which stands for a type that the resolver has not inferred yet.
+    Variable(usize),
+
+    /// The type of something that failed to resolve, which is compatible with every other type,
+    /// so that a single mistake does not cause further errors wherever its result is used.
+    Error,
+}
+
+/// A type parameter of a generic type, along with the traits it must implement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenericParameter {
+    /// The name of the type parameter.
+    pub name: String,
+
+    /// The names of the traits that the type parameter must implement, e.g. `Printable` in `<T: Printable>`.
+    pub bounds: Vec<String>,
+}
+
+impl GenericParameter {
+    pub fn new(name: String, bounds: Vec<String>) -> Self {
+        Self { name, bounds }
+    }
+
+    /// Returns the constraints that the bounds place on the type parameter.
+    pub fn constraints(&self) -> Vec<Constraint> {
+        self.bounds.iter().map(|it| Constraint::new(Type::Parameter(self.name.clone()), it.clone())).collect()
+    }
+}
+
+/// A requirement that a type implements a trait.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constraint {
+    /// The type which must implement the trait.
+    pub r#type: Type,
+
+    /// The name of the trait.
+    pub r#trait: String,
+}
+
+impl Constraint {
+    pub fn new(r#type: Type, r#trait: String) -> Self {
+        Self { r#type, r#trait }
+    }
+
+    /// Replaces any type parameters in the constrained type with their types in the [substitution].
+    pub fn substitute(&self, substitution: &HashMap<String, Type>) -> Constraint {
+        Constraint::new(self.r#type.substitute(substitution), self.r#trait.clone())
+    }
+}
+
+impl Type {
+    /// Creates a union of the [members], flattening any nested unions and removing duplicate members.
+    /// A union of a single type is just that type.
+    pub fn union(members: Vec<Type>) -> Type {
+        let mut flattened = Vec::new();
+        for member in members {
+            let member_types = match member {
+                Type::Union(member_types) => member_types,
+                member => vec![member],
+            };
+
+            for member_type in member_types {
+                if !flattened.contains(&member_type) {
+                    flattened.push(member_type);
+                }
+            }
+        }
+
+        if flattened.len() == 1 {
+            flattened.remove(0)
+        } else {
+            Type::Union(flattened)
+        }
+    }
+
+    /// Replaces any type parameters in this type with their types in the [substitution].
+    /// Type parameters that do not appear in the substitution are left as they are.
+    pub fn substitute(&self, substitution: &HashMap<String, Type>) -> Type {
+        match self {
+            Type::Parameter(name) => substitution.get(name).cloned().unwrap_or_else(|| self.clone()),
+
+            Type::Function(parameters, return_type) => Type::Function(
+                parameters.iter().map(|it| it.substitute(substitution)).collect(),
+                Box::new(return_type.substitute(substitution)),
+            ),
+
+            Type::List(element_type) => Type::List(Box::new(element_type.substitute(substitution))),
+
+            Type::Tuple(elements) => Type::Tuple(elements.iter().map(|it| it.substitute(substitution)).collect()),
+
+            Type::Union(members) => Type::union(members.iter().map(|it| it.substitute(substitution)).collect()),
+
+            Type::Struct(name, arguments) => Type::Struct(
+                name.clone(),
+                arguments.iter().map(|it| it.substitute(substitution)).collect(),
+            ),
+
+            Type::Generic(parameters, r#type) => {
+                // Parameters bound by the inner generic type shadow the outer ones.
+                let mut substitution = substitution.clone();
+                for parameter in parameters {
+                    substitution.remove(&parameter.name);
+                }
+
+                Type::Generic(parameters.clone(), Box::new(r#type.substitute(&substitution)))
+            },
+
+            _ => self.clone(),
+        }
+    }
+
+    /// Returns the type variables that appear in this type, in the order that they first appear.
+    pub fn variables(&self) -> Vec<usize> {
+        let mut variables = Vec::new();
+        self.collect_variables(&mut variables);
+
+        variables
+    }
+
+    /// Returns the names of the types that this type refers to by name, before they are resolved, e.g. `Pair` and `Point`
+    /// in `Pair<Point, Integer>`.
+    pub fn names(&self) -> Vec<&str> {
+        match self {
+            Type::Named(name, arguments, _) => {
+                std::iter::once(name.as_str()).chain(arguments.iter().flat_map(Type::names)).collect()
+            },
+
+            Type::Function(parameters, return_type) => {
+                parameters.iter().chain(std::iter::once(&**return_type)).flat_map(Type::names).collect()
+            },
+
+            Type::List(element_type) => element_type.names(),
+            Type::Tuple(types) | Type::Union(types) => types.iter().flat_map(Type::names).collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Whether every value of this type contains a value of the struct called [name], e.g. `(Node, Integer)` for `Node`.
+    /// A list can be empty, and a union can be another of its members, so neither has to contain one.
+    pub fn always_contains(&self, name: &str) -> bool {
+        match self {
+            Type::Struct(struct_name, _) => struct_name == name,
+            Type::Tuple(elements) => elements.iter().any(|it| it.always_contains(name)),
+            Type::Union(members) => members.iter().all(|it| it.always_contains(name)),
+            _ => false,
+        }
+    }
+
+    /// Returns the type that a generic type is generic over, or the type itself if it is not generic.
+    pub fn without_type_parameters(&self) -> Type {
+        match self {
+            Type::Generic(_, r#type) => (**r#type).clone(),
+            r#type => r#type.clone(),
+        }
+    }
+
+    /// Returns the name that implementations of traits for this type are looked up by,
+    /// or `None` if traits cannot be implemented for this type.
+    pub fn head(&self) -> Option<String> {
+        match self {
+            Type::Integer => Some("Integer".to_string()),
+            Type::String => Some("String".to_string()),
+            Type::Boolean => Some("Boolean".to_string()),
+            Type::List(_) => Some("List".to_string()),
+            Type::Tuple(_) => Some("Tuple".to_string()),
+            Type::Struct(name, _) => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    /// Whether a value can be checked to be of this type at runtime.
+    /// The types that type parameters stand for, the type arguments of structs and the types of functions are not known
+    /// at runtime, so values cannot be checked against types containing them.
+    pub fn is_checkable(&self) -> bool {
+        match self {
+            Type::Any | Type::Void | Type::Integer | Type::String | Type::Boolean => true,
+            Type::List(element_type) => element_type.is_checkable(),
+            Type::Tuple(types) | Type::Union(types) => types.iter().all(Type::is_checkable),
+            Type::Struct(_, arguments) => arguments.is_empty(),
+            _ => false,
+        }
+    }
+
+    fn collect_variables(&self, variables: &mut Vec<usize>) {
+        match self {
+            Type::Variable(variable) if !variables.contains(variable) => variables.push(*variable),
+
+            Type::Function(parameters, return_type) => {
+                for parameter in parameters {
+                    parameter.collect_variables(variables);
+                }
+
+                return_type.collect_variables(variables);
+            },
+
+            Type::List(element_type) => element_type.collect_variables(variables),
+
+            Type::Tuple(types) | Type::Union(types) | Type::Struct(_, types) => {
+                for r#type in types {
+                    r#type.collect_variables(variables);
+                }
+            },
+
+            Type::Generic(_, r#type) => r#type.collect_variables(variables),
+
+            _ => {},
+        }
+    }
 }
 
-impl Default for Type {
-    fn default() -> Self {
-        Self::Unresolved(None)
+/// Writes a comma separated list of types.
+fn write_types(f: &mut std::fmt::Formatter<'_>, types: &[Type]) -> std::fmt::Result {
+    for (index, r#type) in types.iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+
+        write!(f, "{}", r#type)?;
+    }
+
+    Ok(())
+}
+
+/// Types are displayed as they would be written in source code.
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Unresolved => write!(f, "_"),
+            Type::Any => write!(f, "Any"),
+            Type::Void => write!(f, "Void"),
+            Type::Integer => write!(f, "Integer"),
+            Type::String => write!(f, "String"),
+            Type::Boolean => write!(f, "Boolean"),
+
+            Type::Function(parameters, return_type) => {
+                write!(f, "fn(")?;
+                write_types(f, parameters)?;
+                write!(f, "): {}", return_type)
+            },
+
+            Type::List(element_type) => write!(f, "List<{}>", element_type),
+
+            Type::Tuple(elements) => {
+                write!(f, "(")?;
+                write_types(f, elements)?;
+                write!(f, ")")
+            },
+
+            Type::Union(members) => {
+                for (index, member) in members.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " | ")?;
+                    }
+
+                    write!(f, "{}", member)?;
+                }
+
+                Ok(())
+            },
+
+            Type::Struct(name, arguments) | Type::Named(name, arguments, _) => {
+                write!(f, "{}", name)?;
+
+                if !arguments.is_empty() {
+                    write!(f, "<")?;
+                    write_types(f, arguments)?;
+                    write!(f, ">")?;
+                }
+
+                Ok(())
+            },
+
+            Type::Parameter(name) => write!(f, "{}", name),
+
+            Type::Generic(parameters, r#type) => {
+                write!(f, "<")?;
+                for (index, parameter) in parameters.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{}", parameter.name)?;
+
+                    if !parameter.bounds.is_empty() {
+                        write!(f, ": {}", parameter.bounds.join(" + "))?;
+                    }
+                }
+                write!(f, ">{}", r#type)
+            },
+
+            // A type that has not been inferred yet is displayed like one that has not been written yet.
+            Type::Variable(_) => write!(f, "_"),
+
+            Type::Error => write!(f, "{{error}}"),
+        }
     }
 }
@@ -1,5 +1,3 @@
-use colored::Colorize;
-
 use crate::{
     ast::{Expression, Statement},
     Error, ErrorType,
@@ -15,13 +13,11 @@ pub trait StatementVisitor<T> {
             let result = self.visit_statement(statement);
 
             // If the statement is a return statement, stop execution.
-            if let Err(Error {
-                error_type: ErrorType::Return(value),
-                ..
-            }) = result
-            {
-                println!("{} returning: {:?}", "info(visitor):".green(), value);
-                break;
+            if let Err(error) = &result {
+                if let ErrorType::Return(value) = &*error.error_type {
+                    log::debug!("returning: {:?}", value);
+                    break;
+                }
             }
 
             results.push(result?);
@@ -0,0 +1,114 @@
+//! Runs the interpreter with its command line options, and checks what it writes to stdout and stderr.
+//! Only the script itself writes to stdout, so that its output can be used by other programs.
+
+mod common;
+
+/// A script which prints a line and has a warning.
+const WARNED: &str = "let unused = 1\nprint(2)";
+
+/// The escape sequence that starts colouring text in a terminal.
+const COLOUR: &str = "\x1b[";
+
+#[test]
+fn only_the_script_writes_to_stdout() {
+    for arguments in [&[][..], &["-v"], &["-vv"], &["-q"]] {
+        let output = common::run("stdout", WARNED, arguments);
+
+        assert!(output.status.success(), "{:?} stopped the script", arguments);
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "2\n",
+            "{:?} wrote to stdout",
+            arguments
+        );
+    }
+}
+
+#[test]
+fn verbosity_changes_what_is_reported() {
+    let stderr = |arguments: &[&str]| {
+        let output = common::run("verbosity", WARNED, arguments);
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    };
+
+    let normal = stderr(&[]);
+    assert!(normal.starts_with("warning[C0067]") && normal.contains("warning: Found 1 warning"));
+    assert!(
+        !normal.contains("debug("),
+        "the interpreter logged without -v:\n{}",
+        normal
+    );
+
+    assert!(stderr(&["-q"]).is_empty(), "-q reported a warning");
+    assert!(stderr(&["-v"]).contains("debug(interpreter)"), "-v logged nothing");
+
+    // Errors are still reported with -q, but without the summary.
+    let output = common::run("quiet_error", "print(missing)", &["-q"]);
+    let quiet = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(quiet.starts_with("error[C0020]"), "-q hid an error:\n{}", quiet);
+    assert!(!quiet.contains("Found 1 error"), "-q printed the summary:\n{}", quiet);
+}
+
+#[test]
+fn colour_can_be_turned_on_and_off() {
+    let stderr = |colour: &str, no_colour: Option<&str>| {
+        let mut command = common::command("colour", WARNED);
+        command.args(["--color", colour]);
+
+        match no_colour {
+            Some(value) => command.env("NO_COLOR", value),
+            None => command.env_remove("NO_COLOR"),
+        };
+
+        let output = command.output().expect("the interpreter should run");
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    };
+
+    assert!(
+        stderr("always", None).contains(COLOUR),
+        "--color always was not coloured"
+    );
+    assert!(!stderr("never", None).contains(COLOUR), "--color never was coloured");
+
+    // Output is only coloured automatically in a terminal, which the tests do not run in.
+    assert!(!stderr("auto", None).contains(COLOUR), "output to a pipe was coloured");
+    assert!(!stderr("auto", Some("1")).contains(COLOUR), "NO_COLOR was ignored");
+
+    // Asking for colour explicitly takes precedence over `NO_COLOR`.
+    assert!(
+        stderr("always", Some("1")).contains(COLOUR),
+        "--color always was ignored"
+    );
+}
+
+#[test]
+fn tail_calls_are_not_limited_by_the_recursion_limit() {
+    let depth = |call: &str| {
+        format!(
+            "fn depth(value: Any, levels: Integer): Integer {{\n    if value is Integer {{\n        return levels\n    }}\n\n    let list = value as List<Any>\n    return {}\n}}\n\nprint(depth([[[[[7]]]]], 0))",
+            call
+        )
+    };
+
+    let output = common::run(
+        "tail_calls",
+        &depth("depth(list[0], levels + 1)"),
+        &["--recursion-limit", "2"],
+    );
+    assert!(output.status.success(), "the tail calls reached the recursion limit");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "5\n");
+
+    // The same recursion is limited once the result of each call is used by its caller.
+    let output = common::run(
+        "nested_calls",
+        &depth("1 + depth(list[0], levels)"),
+        &["--recursion-limit", "2"],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.starts_with("error[C0019]"),
+        "the recursion limit was not reached:\n{}",
+        stderr
+    );
+}
@@ -0,0 +1,26 @@
+//! Runs the interpreter on scripts for the integration tests, which each include this module.
+
+use std::{
+    fs,
+    path::Path,
+    process::{Command, Output},
+};
+
+/// Writes [source] to a script called [name], and returns a command that runs the interpreter on it.
+/// Each test binary writes its scripts to its own directory, so that their names only need to differ within it.
+pub fn command(name: &str, source: &str) -> Command {
+    let directory = Path::new(env!("CARGO_TARGET_TMPDIR")).join(env!("CARGO_CRATE_NAME"));
+    fs::create_dir_all(&directory).expect("the directory for the scripts should be created");
+
+    let script = directory.join(format!("{}.cec", name));
+    fs::write(&script, source).expect("the script should be written");
+
+    let mut command = Command::new(env!("CARGO_BIN_EXE_cecelia"));
+    command.arg(script);
+    command
+}
+
+/// Writes [source] to a script called [name], and runs the interpreter on it with the given [arguments], without colour.
+pub fn run(name: &str, source: &str, arguments: &[&str]) -> Output {
+    command(name, source).args(arguments).args(["--color", "never"]).output().expect("the interpreter should run")
+}
@@ -0,0 +1,107 @@
+//! Runs scripts with errors in them, and checks what is reported about each error in every message format.
+
+mod common;
+
+/// Runs [source] as a script called [name] with the given [arguments], which should fail, and returns what it reported.
+fn report(name: &str, source: &str, arguments: &[&str]) -> String {
+    let output = common::run(name, source, arguments);
+
+    assert!(!output.status.success(), "{} was not reported as an error", name);
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn json_diagnostics_describe_each_error() {
+    let stderr = report(
+        "json",
+        "let x: Integer = \"a\"\nprint(x)",
+        &["--message-format", "json"],
+    );
+
+    // The path of the script depends on where the tests are run, so only the rest of the error is compared.
+    let errors = stderr.lines().map(|line| line.split_once("\",").map_or(line, |(_, error)| error)).collect::<Vec<_>>();
+
+    assert_eq!(
+        errors,
+        [concat!(
+            r#""severity":"error","code":"C0013","message":"Type mismatch: `Integer` and `String`","#,
+            r#""span":{"start":17,"end":20,"line_start":1,"column_start":18,"line_end":1,"column_end":21},"#,
+            r#""labels":[{"span":{"start":7,"end":14,"line_start":1,"column_start":8,"line_end":1,"column_end":15},"#,
+            r#""message":"expected because `x` is declared as `Integer`"}],"notes":[],"help":[],"trace":[]}"#,
+        )]
+    );
+}
+
+#[test]
+fn misspelled_names_suggest_similar_ones() {
+    let stderr = report("misspelled_function", "prnt(1)", &[]);
+    assert!(
+        stderr.contains("help: a built-in function with a similar name exists: `print`"),
+        "no function was suggested:\n{}",
+        stderr
+    );
+
+    // Swapping two adjacent letters is a single mistake.
+    let stderr = report("misspelled_variable", "let count = 1\nprint(cuont)", &[]);
+    assert!(
+        stderr.contains("help: a variable with a similar name exists: `count`"),
+        "no variable was suggested:\n{}",
+        stderr
+    );
+
+    let stderr = report("unrelated_name", "let count = 1\nprint(total)", &[]);
+    assert!(
+        !stderr.contains("help:"),
+        "an unrelated name was suggested:\n{}",
+        stderr
+    );
+}
+
+/// Returns the lines of the stack trace in [stderr], with the path of the script left out of each frame,
+/// e.g. `at main:4`.
+fn trace(stderr: &str) -> Vec<String> {
+    stderr
+        .lines()
+        .skip_while(|line| *line != "stack trace (most recent call last):")
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .map(|line| match line.trim().split_once(" (") {
+            Some((frame, location)) => {
+                let line = location.trim_end_matches(')').rsplit_once(':').map_or("", |(_, line)| line);
+                format!("{}:{}", frame, line)
+            },
+            None => line.trim().to_string(),
+        })
+        .collect()
+}
+
+#[test]
+fn runtime_errors_show_the_calls_that_led_to_them() {
+    let stderr = report(
+        "division_trace",
+        "fn inner(n: Integer): Integer {\n    return 10 / n\n}\nfn outer(n: Integer): Integer {\n    return inner(n) + 1\n}\nprint(outer(0))",
+        &[],
+    );
+    assert!(
+        stderr.starts_with("error[C0017]"),
+        "the wrong error was reported:\n{}",
+        stderr
+    );
+    assert_eq!(trace(&stderr), ["at <main>:7", "at outer:5", "at inner:2"]);
+
+    // Repeated calls of the same function from the same place are collapsed into one line.
+    let stderr = report(
+        "recursion_trace",
+        "fn down(n: Integer): Integer {\n    return 1 + down(n - 1)\n}\nprint(down(1))",
+        &["--recursion-limit", "10"],
+    );
+    assert!(
+        stderr.starts_with("error[C0019]"),
+        "the wrong error was reported:\n{}",
+        stderr
+    );
+    assert_eq!(
+        trace(&stderr),
+        ["at <main>:4", "at down:2", "... and 9 more identical calls"]
+    );
+}
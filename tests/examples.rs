@@ -0,0 +1,42 @@
+//! Runs each of the example scripts in `example`, and compares what it prints with the output checked in alongside the
+//! tests in `tests/examples`, which has the same name as the script.
+
+mod common;
+
+use std::{fs, path::Path};
+
+#[test]
+fn examples_print_their_expected_output() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut scripts = fs::read_dir(root.join("example"))
+        .expect("the examples should exist")
+        .map(|entry| entry.expect("the examples should be readable").path())
+        .filter(|path| path.extension().is_some_and(|it| it == "cec"))
+        .collect::<Vec<_>>();
+
+    scripts.sort();
+
+    for script in scripts {
+        let name = script.file_stem().expect("an example should have a name").to_string_lossy();
+        let expected = fs::read_to_string(root.join("tests/examples").join(format!("{}.out", name)))
+            .unwrap_or_else(|_| panic!("{} should have an expected output", script.display()));
+
+        let source = fs::read_to_string(&script).expect("the example should be readable");
+        let output = common::run(&name, &source, &[]);
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            output.status.success() && stderr.is_empty(),
+            "{} reported a diagnostic:\n{}",
+            script.display(),
+            stderr
+        );
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            expected,
+            "{} printed the wrong output",
+            script.display()
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Runs the examples in the explanation of each error code, which alternate between code that causes the error and the
+//! same code fixed, to ensure that the explanations stay true to what the interpreter reports.
+
+mod common;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Returns the path of every explanation, along with the code that it explains.
+fn explanations() -> Vec<(String, PathBuf)> {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/error/explanations");
+    let mut explanations = fs::read_dir(directory)
+        .expect("the explanations should exist")
+        .map(|entry| entry.expect("the explanations should be readable").path())
+        .filter(|path| path.extension().is_some_and(|it| it == "md"))
+        .map(|path| {
+            let code = path.file_stem().expect("an explanation should be named by its code").to_string_lossy();
+            (code.into_owned(), path)
+        })
+        .collect::<Vec<_>>();
+
+    explanations.sort();
+    explanations
+}
+
+/// Returns the code of each `cecelia` example in an [explanation], in the order that they appear.
+fn examples(explanation: &str) -> Vec<String> {
+    let mut examples = Vec::new();
+    let mut example: Option<String> = None;
+
+    for line in explanation.lines() {
+        match &mut example {
+            None if line == "```cecelia" => example = Some(String::new()),
+            Some(_) if line == "```" => examples.extend(example.take()),
+            Some(code) => {
+                code.push_str(line);
+                code.push('\n');
+            },
+            None => {},
+        }
+    }
+
+    examples
+}
+
+#[test]
+fn erroneous_examples_report_their_code() {
+    for (code, path) in explanations() {
+        let explanation = fs::read_to_string(&path).expect("the explanation should be readable");
+
+        for (index, example) in examples(&explanation).iter().enumerate().step_by(2) {
+            let output = common::run(&format!("{}_{}", code, index), example, &[]);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+
+            assert!(
+                stderr.contains(&format!("[{}]", code)),
+                "example {} of {} does not report it:\n{}\n{}",
+                index + 1,
+                code,
+                example,
+                stderr
+            );
+        }
+    }
+}
+
+#[test]
+fn fixed_examples_report_nothing() {
+    for (code, path) in explanations() {
+        let explanation = fs::read_to_string(&path).expect("the explanation should be readable");
+
+        for (index, example) in examples(&explanation).iter().enumerate().skip(1).step_by(2) {
+            let output = common::run(&format!("{}_{}", code, index), example, &[]);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+
+            assert!(
+                output.status.success() && stderr.is_empty(),
+                "example {} of {} reports a diagnostic:\n{}\n{}",
+                index + 1,
+                code,
+                example,
+                stderr
+            );
+        }
+    }
+}
+
+#[test]
+fn every_explanation_can_be_shown() {
+    for (code, _) in explanations() {
+        let output = Command::new(env!("CARGO_BIN_EXE_cecelia"))
+            .args(["explain", &code])
+            .output()
+            .expect("the interpreter should run");
+
+        assert!(output.status.success(), "{} cannot be explained", code);
+    }
+}
@@ -0,0 +1,170 @@
+//! Runs the interpreter on a corpus of malformed scripts in `tests/malformed`, each of which should be reported as an
+//! error in every message format without the interpreter panicking, however the script ends.
+
+mod common;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Output,
+};
+
+/// The longest line that the interpreter should print when reporting an error, as long lines of code are cut off.
+const MAX_OUTPUT_WIDTH: usize = 200;
+
+/// Returns the path of every script in the corpus.
+fn scripts() -> Vec<PathBuf> {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/malformed");
+    let mut scripts = fs::read_dir(directory)
+        .expect("the corpus of malformed scripts should exist")
+        .map(|entry| entry.expect("the corpus should be readable").path())
+        .filter(|path| path.extension().is_some_and(|it| it == "cec"))
+        .collect::<Vec<_>>();
+
+    scripts.sort();
+    scripts
+}
+
+/// Runs the interpreter on the [script] from the corpus with the given [arguments], without colour.
+fn run(script: &Path, arguments: &[&str]) -> Output {
+    let name = script.file_stem().expect("a script should have a name").to_string_lossy();
+    let source = fs::read_to_string(script).expect("the script should be readable");
+
+    common::run(&name, &source, arguments)
+}
+
+/// Ensures that the interpreter failed on [script] with an error, rather than succeeding or panicking.
+fn assert_failed(script: &Path, output: &Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+
+    assert!(
+        !stderr.contains("panicked"),
+        "{} panicked:\n{}",
+        script.display(),
+        stderr
+    );
+    assert!(
+        !output.status.success(),
+        "{} was not reported as an error:\n{}",
+        script.display(),
+        stderr
+    );
+    assert!(output.stdout.is_empty(), "{} printed to stdout", script.display());
+
+    stderr
+}
+
+#[test]
+fn malformed_scripts_are_reported() {
+    for script in scripts() {
+        let output = run(&script, &[]);
+        let stderr = assert_failed(&script, &output);
+
+        assert!(
+            stderr.starts_with("error["),
+            "{} has no error code:\n{}",
+            script.display(),
+            stderr
+        );
+        assert!(
+            stderr.contains(" --> line "),
+            "{} has no location:\n{}",
+            script.display(),
+            stderr
+        );
+
+        for line in stderr.lines() {
+            assert!(
+                line.chars().count() <= MAX_OUTPUT_WIDTH,
+                "{} printed a line which was not cut off:\n{}",
+                script.display(),
+                line
+            );
+        }
+    }
+}
+
+#[test]
+fn long_lines_show_the_error_rather_than_labels_far_from_it() {
+    let script = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/malformed/long_line_label_far_from_error.cec");
+    let output = run(&script, &[]);
+    let stderr = assert_failed(&script, &output);
+
+    let lines = stderr.lines().collect::<Vec<_>>();
+    let underline = lines.iter().position(|line| line.contains('^')).expect("the error should be underlined");
+
+    let text = lines[underline - 1].chars().collect::<Vec<_>>();
+    let underlined = lines[underline]
+        .char_indices()
+        .filter(|(_, char)| *char == '^')
+        .map(|(index, _)| text[index])
+        .collect::<String>();
+
+    assert_eq!(underlined, "xs", "the wrong code was underlined:\n{}", stderr);
+}
+
+#[test]
+fn malformed_scripts_are_reported_as_json() {
+    for script in scripts() {
+        let output = run(&script, &["--message-format", "json"]);
+        let stderr = assert_failed(&script, &output);
+
+        for line in stderr.lines() {
+            assert!(
+                line.starts_with("{\"file\":") && line.ends_with('}'),
+                "{} printed a line which is not a JSON error:\n{}",
+                script.display(),
+                line
+            );
+        }
+    }
+}
+
+#[test]
+fn empty_scripts_run() {
+    for (name, input) in [
+        ("empty", ""),
+        ("newlines", "\n\n\n"),
+        ("comment", "// Nothing to see here"),
+    ] {
+        let output = common::run(name, input, &[]);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        assert!(
+            output.status.success(),
+            "{} was reported as an error:\n{}",
+            name,
+            stderr
+        );
+        assert!(output.stdout.is_empty() && stderr.is_empty(), "{} printed output", name);
+    }
+}
+
+#[test]
+fn deeply_nested_code_is_reported_rather_than_overflowing_the_stack() {
+    // Each call is nested inside hundreds of operations, so the stack runs out well before the recursion limit.
+    let terms = "1 + ".repeat(300);
+    let recursive = format!(
+        "fn down(x: Integer): Integer {{\n    return {}down(x)\n}}\nprint(down(1))",
+        terms
+    );
+
+    let output = common::run("deeply_nested_recursion", &recursive, &[]);
+    let stderr = assert_failed(Path::new("deeply_nested_recursion"), &output);
+    assert!(
+        stderr.starts_with("error[C0019]") && stderr.contains(" --> line 2"),
+        "the stack overflow was not reported where it happened:\n{}",
+        stderr
+    );
+
+    // A long enough expression can run out of stack space while it is parsed or resolved, before anything runs.
+    let flat = format!("let x = {}1\nprint(x)", "1 + ".repeat(5000));
+
+    let output = common::run("long_expression", &flat, &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        output.status.success() || stderr.starts_with("error[C0074]"),
+        "the long expression was neither run nor reported:\n{}",
+        stderr
+    );
+}
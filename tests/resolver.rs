@@ -0,0 +1,350 @@
+//! Runs small scripts through the interpreter to check what the type resolver accepts and what it reports.
+
+mod common;
+
+/// What running a script printed, and the diagnostics it reported.
+struct Run {
+    success: bool,
+    stdout: String,
+
+    /// Each diagnostic's severity and code, in the order they were reported, e.g. `error[C0013]`.
+    diagnostics: Vec<String>,
+}
+
+/// Runs [source] as a script called [name] with the given [arguments], without colour.
+fn run(name: &str, source: &str, arguments: &[&str]) -> Run {
+    let output = common::run(name, source, arguments);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let diagnostics = stderr
+        .lines()
+        .filter(|line| line.starts_with("error[") || line.starts_with("warning["))
+        .filter_map(|line| line.split_once(':').map(|(diagnostic, _)| diagnostic.to_string()))
+        .collect();
+
+    Run {
+        success: output.status.success(),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        diagnostics,
+    }
+}
+
+/// Ensures that [source] runs without any diagnostics, printing the [expected] output.
+fn assert_runs(name: &str, source: &str, expected: &str) {
+    let run = run(name, source, &[]);
+
+    assert!(
+        run.success && run.diagnostics.is_empty(),
+        "{} reported {:?}",
+        name,
+        run.diagnostics
+    );
+    assert_eq!(run.stdout, expected, "{} printed the wrong output", name);
+}
+
+/// Ensures that [source] fails without running, reporting exactly the [expected] diagnostics.
+fn assert_reports(name: &str, source: &str, expected: &[&str]) {
+    let run = run(name, source, &[]);
+
+    assert!(!run.success, "{} was not reported as an error", name);
+    assert!(run.stdout.is_empty(), "{} ran despite its errors", name);
+    assert_eq!(run.diagnostics, expected, "{} reported the wrong diagnostics", name);
+}
+
+#[test]
+fn unions_accept_each_of_their_members() {
+    assert_runs(
+        "union_members",
+        r#"
+fn describe(value: Integer | String): String {
+    if value is Integer {
+        return "number"
+    }
+
+    return value
+}
+
+print(describe(1))
+print(describe("text"))
+"#,
+        "number\ntext\n",
+    );
+
+    assert_runs(
+        "union_list_literal",
+        r#"
+let mut values: List<Integer | String> = [1, "a"]
+values.push(2)
+print(values)
+"#,
+        "[1, a, 2]\n",
+    );
+}
+
+#[test]
+fn unions_reject_other_types() {
+    assert_reports(
+        "union_other_type",
+        "let value: Integer | String = true\nprint(value)",
+        &["error[C0013]"],
+    );
+
+    assert_reports(
+        "union_indistinguishable",
+        "fn first(values: List<Integer> | List<String>) {}\nfirst([1])",
+        &["error[C0034]"],
+    );
+}
+
+#[test]
+fn narrowing_only_changes_the_type_read() {
+    assert_runs(
+        "narrowed_assignment",
+        r#"
+let mut value: Integer | String = 1
+if value is String {
+    return
+}
+
+print(value + 1)
+value = "text"
+print(value)
+if value is String {
+    value = 2
+    print(value)
+}
+"#,
+        "2\ntext\n2\n",
+    );
+
+    // Once assigned to, even in a branch, a variable could hold any type it was declared with.
+    assert_reports(
+        "narrowing_dropped",
+        "let mut value: Integer | String = 1\nif value is String {\n    return\n}\nif true {\n    value = \"text\"\n}\nprint(value + 1)",
+        &["error[C0033]"],
+    );
+}
+
+#[test]
+fn inferred_functions_are_generic() {
+    assert_runs(
+        "generalised_variable",
+        "let id = |x| x\nlet same = id\nprint(same(1))\nprint(same(\"a\"))",
+        "1\na\n",
+    );
+
+    let add = "fn add(a, b) {\n    return a + b\n}\n";
+    assert_runs(
+        "inferred_addition",
+        &format!("{}print(add(1, 2))\nprint(add(\"a\", \"b\"))", add),
+        "3\nab\n",
+    );
+    assert_reports(
+        "inferred_addition_of_booleans",
+        &format!("{}print(add(true, false))", add),
+        &["error[C0041]"],
+    );
+}
+
+#[test]
+fn traits_are_required_by_bounds() {
+    let describe = r#"
+trait Describe {
+    fn describe(self): String
+}
+
+impl Describe for Integer {
+    fn describe(self) {
+        return "the number " + display(self)
+    }
+}
+
+fn announce<T: Describe>(value: T) {
+    print(describe(value))
+}
+"#;
+
+    assert_runs(
+        "trait_implemented",
+        &format!("{}announce(1)", describe),
+        "the number 1\n",
+    );
+    assert_reports(
+        "trait_not_implemented",
+        &format!("{}announce(\"one\")", describe),
+        &["error[C0043]"],
+    );
+}
+
+#[test]
+fn only_mutable_variables_can_be_modified() {
+    assert_runs(
+        "mutable",
+        "let mut values = [1]\nvalues.push(2)\nvalues[0] = 3\nprint(values)",
+        "[3, 2]\n",
+    );
+
+    assert_reports(
+        "immutable",
+        "let values = [1]\nvalues.push(2)\nprint(values)",
+        &["error[C0065]"],
+    );
+    assert_reports(
+        "modified_in_function",
+        "let mut count = 0\nfn increment() {\n    count = 1\n}\nincrement()",
+        &["error[C0066]"],
+    );
+    assert_reports(
+        "captured_mutable",
+        "let mut count = 1\nlet get = || count\ncount = 5\nprint(get())",
+        &["error[C0071]"],
+    );
+}
+
+#[test]
+fn places_are_only_evaluated_once() {
+    assert_runs(
+        "place_indices",
+        r#"
+fn first(): Integer {
+    print("index")
+    return 0
+}
+
+let mut grid = [[1, 2], [3]]
+grid[first()][0] = 9
+grid[first()].push(3)
+print(grid)
+"#,
+        "index\nindex\n[[9, 2, 3], [3]]\n",
+    );
+}
+
+#[test]
+fn arguments_can_be_passed_by_name() {
+    let pad = "fn pad(text: String, width: Integer = 2, fill: String = \" \"): String {\n    return text + fill + display(width)\n}\n";
+
+    assert_runs(
+        "named_arguments",
+        &format!(
+            "{}print(pad(\"a\", fill: \"-\"))\nprint(pad(width: 5, text: \"b\"))",
+            pad
+        ),
+        "a-2\nb 5\n",
+    );
+
+    // Every argument that does not match a parameter is reported, not only the first.
+    assert_reports(
+        "mismatched_arguments",
+        &format!("{}print(pad(width: 1, width: 2, colour: \"red\"))", pad),
+        &["error[C0055]", "error[C0056]", "error[C0054]"],
+    );
+    assert_reports(
+        "positional_after_named",
+        &format!("{}print(pad(text: \"a\", 3))", pad),
+        &["error[C0057]"],
+    );
+}
+
+#[test]
+fn lints_can_be_allowed_or_denied() {
+    let unused = "let width = 1\nlet _height = 2\nprint(3)";
+
+    let warned = run("lint_warned", unused, &[]);
+    assert!(warned.success, "a warning stopped the program");
+    assert_eq!(warned.stdout, "3\n");
+    assert_eq!(warned.diagnostics, ["warning[C0067]"]);
+
+    let denied = run("lint_denied", unused, &["-D", "unused"]);
+    assert!(
+        !denied.success && denied.stdout.is_empty(),
+        "a denied lint did not stop the program"
+    );
+    assert_eq!(denied.diagnostics, ["error[C0067]"]);
+
+    assert!(run("lint_allowed", unused, &["-A", "unused"]).diagnostics.is_empty());
+    assert_runs(
+        "lint_allowed_by_comment",
+        &format!("// cecelia:allow(unused)\n{}", unused),
+        "3\n",
+    );
+
+    let function = run(
+        "lints_in_function",
+        "let x = 1\nfn f(): Integer {\n    let x = 2\n    return x\n    print(x)\n}\nprint(f())",
+        &[],
+    );
+    assert_eq!(function.stdout, "2\n");
+    assert_eq!(
+        function.diagnostics,
+        ["warning[C0067]", "warning[C0069]", "warning[C0068]"]
+    );
+}
+
+#[test]
+fn functions_are_only_called_once_declared() {
+    let double = "fn double(x: Integer): Integer {\n    return x * 2\n}\n";
+    let quadruple = "fn quadruple(x: Integer): Integer {\n    return double(double(x))\n}\n";
+
+    assert_runs(
+        "declared_later",
+        &format!("{}{}print(quadruple(2))", quadruple, double),
+        "8\n",
+    );
+    assert_reports(
+        "called_before_declared",
+        &format!("{}print(quadruple(2))\n{}", quadruple, double),
+        &["error[C0021]"],
+    );
+
+    let method = "struct Number {\n    value: Integer\n}\nimpl Number {\n    fn doubled(self): Integer {\n        return double(self.value)\n    }\n}\n";
+    assert_runs(
+        "method_declared_later",
+        &format!("{}{}print(Number {{ value: 3 }}.doubled())", method, double),
+        "6\n",
+    );
+    assert_reports(
+        "method_called_before_declared",
+        &format!("{}print(Number {{ value: 3 }}.doubled())\n{}", method, double),
+        &["error[C0021]"],
+    );
+}
+
+#[test]
+fn names_are_only_declared_once() {
+    assert_reports(
+        "duplicate_field",
+        "struct Point {\n    x: Integer\n    x: String\n}",
+        &["error[C0040]"],
+    );
+    assert_reports(
+        "duplicate_type_parameter",
+        "fn pair<T, T>(first: T, second: T): (T, T) {\n    return (first, second)\n}",
+        &["error[C0075]"],
+    );
+}
+
+#[test]
+fn values_of_any_type_are_checked_when_used() {
+    assert_runs(
+        "any_operands",
+        "let value: Any = 2\nlet sum: Integer = 1 + value\nprint(sum)\nprint([1, value])\nprint(value)",
+        "3\n[1, 2]\n2\n",
+    );
+
+    let run = run(
+        "any_operand_mismatch",
+        "let value: Any = \"text\"\nprint(1 + value)",
+        &[],
+    );
+    assert!(!run.success, "adding a string to an integer was not reported");
+    assert_eq!(run.diagnostics, ["error[C0037]"]);
+}
+
+#[test]
+fn operators_are_applied_by_precedence_from_left_to_right() {
+    assert_runs(
+        "operator_precedence",
+        "const QUOTIENT = 100 / 10 / 5\nprint(QUOTIENT)\nlet ten = 10\nprint(ten - 3 - 2)\nprint(2 + 3 * 4)\nprint(ten / 5 * 2 - 1)",
+        "2\n5\n14\n3\n",
+    );
+}